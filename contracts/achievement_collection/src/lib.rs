@@ -1,6 +1,11 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, token, Address, Env, IntoVal, Map, String, Symbol, Val,
+    Vec,
+};
+
+const MAX_FIRST_COMPLETERS: u32 = 100;
 
 #[contracttype]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -17,20 +22,94 @@ pub struct Set {
     pub id: u32,
     pub name: String,
     pub achievements: Vec<u32>,
+    pub required_sets: Vec<u32>, // Sets that must be completed first (meta sets)
     pub rarity: Rarity,
     pub limited_cap: Option<u32>, // Total max claims per achievement across players
     pub bonus_points: i128,       // Internal bonus points rewarded on completion
 }
 
+// Set layout stored before meta sets existed; read only by `migrate_sets`
+#[contracttype]
+#[derive(Clone)]
+pub struct LegacySet {
+    pub id: u32,
+    pub name: String,
+    pub achievements: Vec<u32>,
+    pub rarity: Rarity,
+    pub limited_cap: Option<u32>,
+    pub bonus_points: i128,
+}
+
+// One row of a player's nested progress through a set and the sets it requires
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SetProgress {
+    pub set_id: u32,
+    pub depth: u32,     // 0 for the queried set
+    pub collected: u32, // achievements collected plus required sets completed
+    pub required: u32,
+    pub completed: bool,
+}
+
+// Mirror of achievement_nft's token record, decoded from `get_achievement`
+#[contracttype]
+#[derive(Clone)]
+pub struct NftAchievement {
+    pub owner: Address,
+    pub puzzle_id: u32,
+    pub metadata: String,
+    pub timestamp: u64,
+}
+
+// Seasonal window during which a set can be completed
+#[contracttype]
+#[derive(Clone)]
+pub struct SetWindow {
+    pub opens_at: u64,
+    pub closes_at: u64,
+}
+
+// How completion bonuses are paid out
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RewardPayout {
+    Internal,       // credited to the Bonus ledger
+    Token(Address), // transferred from this contract's SEP-41 balance
+    Mint(Address),  // minted from reward_token; this contract must be a minter
+}
+
+// Which player an NFT's progress is currently credited to
+#[contracttype]
+#[derive(Clone)]
+pub struct TokenProgress {
+    pub holder: Address,
+    pub achievement_id: u32,
+}
+
 #[contracttype]
 pub enum DataKey {
     NextSetId,
-    Set(u32),                // Set
-    AchToSet(u32),           // u32 set_id
+    Set(u32),                     // Set
+    AchToSet(u32),                // u32 set_id, legacy single-set index replaced by AchToSets
+    AchToSets(u32),               // Vec<u32> set ids the achievement counts toward
+    SetParents(u32),              // Vec<u32> meta set ids requiring the set
     PlayerProgress(Address, u32), // Vec<u32> collected achievement IDs in set
-    AchCount(u32),           // u32 global claim count for limited edition
-    Bonus(Address),          // i128 internal bonus ledger per player
-    Completed(Address, u32), // bool marker to avoid double bonus
+    AchCount(u32),                // u32 global claim count for limited edition
+    Bonus(Address),               // i128 internal bonus ledger per player
+    Completed(Address, u32),      // bool marker to avoid double bonus
+    PendingBonus(Address),        // Persistent: i128 token bonus accrued but not yet claimed
+    TokenPaidSets(u32),           // Persistent: Vec<u32> sets the token already paid out for
+    Unrewarded(Address, u32),     // Persistent: bool completion carried by already paid tokens
+    Admin,                        // Address
+    NftContract,                  // Address of the bound achievement_nft contract
+    Recorder(Address),            // bool allowlist of trusted recorders
+    TokenProgress(u32),           // Persistent: TokenProgress for an NFT token id
+    TokenBacking(Address, u32),   // Persistent: u32 recorded tokens backing a player's achievement
+    RecorderBacked(Address, u32), // Persistent: bool progress credited by a recorder, or held before NFT proof
+    SetWindow(u32),               // SetWindow for seasonal sets
+    RewardPayout,                 // RewardPayout
+    CompletionCount(u32),         // u32 players who completed a set
+    FirstCompleters(u32),         // Vec<Address> in completion order, capped
 }
 
 #[contract]
@@ -38,11 +117,112 @@ pub struct AchievementCollection;
 
 #[contractimpl]
 impl AchievementCollection {
-    pub fn initialize(env: Env) {
-        if env.storage().instance().has(&DataKey::NextSetId) {
+    // Deployments from before admins existed already have sets; they keep them and
+    // only gain an admin
+    pub fn initialize(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
             panic!("initialized");
         }
-        env.storage().instance().set(&DataKey::NextSetId, &1u32);
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        if !env.storage().instance().has(&DataKey::NextSetId) {
+            env.storage().instance().set(&DataKey::NextSetId, &1u32);
+        }
+    }
+
+    fn require_admin(env: &Env) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("admin");
+        admin.require_auth();
+    }
+
+    // Bind the achievement_nft contract whose tokens prove achievements. Achievement
+    // ids are the NFTs' puzzle ids, and progress then follows the tokens.
+    pub fn set_nft_contract(env: Env, nft_contract: Address) {
+        Self::require_admin(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::NftContract, &nft_contract);
+    }
+
+    pub fn get_nft_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::NftContract)
+    }
+
+    // Seasonal sets: progress can only be recorded, and completion only counts, in the window
+    pub fn set_season_window(env: Env, set_id: u32, opens_at: u64, closes_at: u64) {
+        Self::require_admin(&env);
+        if !env.storage().instance().has(&DataKey::Set(set_id)) {
+            panic!("set");
+        }
+        if closes_at <= opens_at {
+            panic!("invalid window");
+        }
+        env.storage().instance().set(
+            &DataKey::SetWindow(set_id),
+            &SetWindow {
+                opens_at,
+                closes_at,
+            },
+        );
+    }
+
+    pub fn get_season_window(env: Env, set_id: u32) -> Option<SetWindow> {
+        env.storage().instance().get(&DataKey::SetWindow(set_id))
+    }
+
+    pub fn set_reward_payout(env: Env, payout: RewardPayout) {
+        Self::require_admin(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::RewardPayout, &payout);
+    }
+
+    pub fn get_reward_payout(env: Env) -> RewardPayout {
+        env.storage()
+            .instance()
+            .get(&DataKey::RewardPayout)
+            .unwrap_or(RewardPayout::Internal)
+    }
+
+    pub fn completion_count(env: Env, set_id: u32) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::CompletionCount(set_id))
+            .unwrap_or(0)
+    }
+
+    // The first players to complete a set, in order (up to MAX_FIRST_COMPLETERS)
+    pub fn first_completers(env: Env, set_id: u32) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::FirstCompleters(set_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    // Recorder allowlist
+    pub fn add_recorder(env: Env, recorder: Address) {
+        Self::require_admin(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::Recorder(recorder), &true);
+    }
+
+    pub fn remove_recorder(env: Env, recorder: Address) {
+        Self::require_admin(&env);
+        env.storage()
+            .instance()
+            .remove(&DataKey::Recorder(recorder));
+    }
+
+    pub fn is_recorder(env: Env, recorder: Address) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Recorder(recorder))
+            .unwrap_or(false)
     }
 
     // Set management
@@ -54,9 +234,55 @@ impl AchievementCollection {
         limited_cap: Option<u32>,
         bonus_points: i128,
     ) -> u32 {
-        if achievements.len() == 0 {
+        Self::require_admin(&env);
+        if achievements.is_empty() {
+            panic!("empty set");
+        }
+        Self::store_set(
+            &env,
+            name,
+            achievements,
+            Vec::new(&env),
+            rarity,
+            limited_cap,
+            bonus_points,
+        )
+    }
+
+    // Collector sets: completed once every required set is completed, plus any
+    // achievements of their own
+    pub fn create_meta_set(
+        env: Env,
+        name: String,
+        achievements: Vec<u32>,
+        required_sets: Vec<u32>,
+        rarity: Rarity,
+        bonus_points: i128,
+    ) -> u32 {
+        Self::require_admin(&env);
+        if required_sets.is_empty() {
             panic!("empty set");
         }
+        Self::store_set(
+            &env,
+            name,
+            achievements,
+            required_sets,
+            rarity,
+            None,
+            bonus_points,
+        )
+    }
+
+    fn store_set(
+        env: &Env,
+        name: String,
+        achievements: Vec<u32>,
+        required_sets: Vec<u32>,
+        rarity: Rarity,
+        limited_cap: Option<u32>,
+        bonus_points: i128,
+    ) -> u32 {
         let id: u32 = env
             .storage()
             .instance()
@@ -66,7 +292,8 @@ impl AchievementCollection {
         let set = Set {
             id,
             name,
-            achievements: achievements.clone(),
+            achievements: Vec::new(env),
+            required_sets: Vec::new(env),
             rarity,
             limited_cap,
             bonus_points,
@@ -74,12 +301,11 @@ impl AchievementCollection {
         env.storage().instance().set(&DataKey::Set(id), &set);
         env.storage().instance().set(&DataKey::NextSetId, &(id + 1));
 
-        for a in achievements.iter() {
-            let aid = a.clone();
-            if env.storage().instance().has(&DataKey::AchToSet(aid)) {
-                panic!("achievement already mapped");
-            }
-            env.storage().instance().set(&DataKey::AchToSet(aid), &id);
+        for achievement_id in achievements.iter() {
+            Self::link_achievement(env, id, achievement_id);
+        }
+        for child_id in required_sets.iter() {
+            Self::link_set(env, id, child_id);
         }
         id
     }
@@ -88,55 +314,339 @@ impl AchievementCollection {
         env.storage().instance().get(&DataKey::Set(set_id))
     }
 
-    // Map additional achievement to an existing set
+    // Map additional achievement to an existing set; an achievement may count toward
+    // any number of sets
     pub fn add_achievement_to_set(env: Env, set_id: u32, achievement_id: u32) {
-        let mut set: Set = env
-            .storage()
-            .instance()
-            .get(&DataKey::Set(set_id))
-            .expect("set");
-        if set.achievements.contains(&achievement_id) {
+        Self::require_admin(&env);
+        Self::link_achievement(&env, set_id, achievement_id);
+    }
+
+    fn link_achievement(env: &Env, set_id: u32, achievement_id: u32) {
+        let mut set = Self::load_set(env, set_id);
+        if set.achievements.contains(achievement_id) {
             return;
         }
-        if env.storage().instance().has(&DataKey::AchToSet(achievement_id)) {
-            panic!("achievement already mapped");
-        }
         set.achievements.push_back(achievement_id);
         env.storage().instance().set(&DataKey::Set(set_id), &set);
-        env.storage()
-            .instance()
-            .set(&DataKey::AchToSet(achievement_id), &set_id);
+
+        let mut sets = Self::sets_of(env, achievement_id);
+        if !sets.contains(set_id) {
+            sets.push_back(set_id);
+            env.storage()
+                .instance()
+                .set(&DataKey::AchToSets(achievement_id), &sets);
+        }
     }
 
-    // Record progress and detect completion
-    pub fn record_achievement(env: Env, player: Address, achievement_id: u32) -> bool {
-        player.require_auth();
-        let set_id: u32 = env
-            .storage()
+    // Require completion of `child_id` for `parent_id`; rejects links that would loop
+    pub fn add_set_to_set(env: Env, parent_id: u32, child_id: u32) {
+        Self::require_admin(&env);
+        Self::link_set(&env, parent_id, child_id);
+    }
+
+    fn link_set(env: &Env, parent_id: u32, child_id: u32) {
+        let mut parent = Self::load_set(env, parent_id);
+        if !env.storage().instance().has(&DataKey::Set(child_id)) {
+            panic!("set");
+        }
+        if parent.required_sets.contains(child_id) {
+            return;
+        }
+        if child_id == parent_id || Self::requires_set(env, child_id, parent_id) {
+            panic!("cycle");
+        }
+        parent.required_sets.push_back(child_id);
+        env.storage()
             .instance()
-            .get(&DataKey::AchToSet(achievement_id))
-            .expect("mapped");
+            .set(&DataKey::Set(parent_id), &parent);
 
-        let set: Set = env
-            .storage()
+        let mut parents = Self::parents_of(env, child_id);
+        parents.push_back(parent_id);
+        env.storage()
             .instance()
-            .get(&DataKey::Set(set_id))
-            .expect("set");
+            .set(&DataKey::SetParents(child_id), &parents);
+    }
 
-        // limited edition check per achievement (only when acquiring new)
-        // load current progress to determine ownership first
-        let progress_for_cap: Vec<u32> = env
+    // Admin: rewrite sets stored before meta sets existed and move their achievements
+    // from the single-set AchToSet index into AchToSets. Covers set ids
+    // `start_id..start_id + limit` and returns how many sets were migrated
+    pub fn migrate_sets(env: Env, start_id: u32, limit: u32) -> u32 {
+        Self::require_admin(&env);
+        let next_id: u32 = env
             .storage()
             .instance()
-            .get(&DataKey::PlayerProgress(player.clone(), set_id))
-            .unwrap_or(Vec::new(&env.clone()));
-        if let Some(cap) = set.limited_cap {
-            let mut cnt: u32 = env
+            .get(&DataKey::NextSetId)
+            .unwrap_or(1);
+        let end_id = start_id.saturating_add(limit).min(next_id);
+        let mut migrated = 0u32;
+
+        for set_id in start_id.max(1)..end_id {
+            let fields: Map<Symbol, Val> = match env.storage().instance().get(&DataKey::Set(set_id))
+            {
+                Some(fields) => fields,
+                None => continue,
+            };
+            if fields.contains_key(Symbol::new(&env, "required_sets")) {
+                continue;
+            }
+            let legacy: LegacySet = env
                 .storage()
                 .instance()
-                .get(&DataKey::AchCount(achievement_id))
-                .unwrap_or(0);
-            if !progress_for_cap.contains(&achievement_id) {
+                .get(&DataKey::Set(set_id))
+                .expect("set");
+
+            let set = Set {
+                id: legacy.id,
+                name: legacy.name,
+                achievements: Vec::new(&env),
+                required_sets: Vec::new(&env),
+                rarity: legacy.rarity,
+                limited_cap: legacy.limited_cap,
+                bonus_points: legacy.bonus_points,
+            };
+            env.storage().instance().set(&DataKey::Set(set_id), &set);
+            for achievement_id in legacy.achievements.iter() {
+                env.storage()
+                    .instance()
+                    .remove(&DataKey::AchToSet(achievement_id));
+                Self::link_achievement(&env, set_id, achievement_id);
+            }
+            migrated += 1;
+        }
+        migrated
+    }
+
+    // Sets an achievement counts toward
+    fn sets_of(env: &Env, achievement_id: u32) -> Vec<u32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::AchToSets(achievement_id))
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Meta sets that require a set
+    fn parents_of(env: &Env, set_id: u32) -> Vec<u32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::SetParents(set_id))
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Record progress on behalf of a player; only allowlisted recorders may call this
+    pub fn record_achievement(
+        env: Env,
+        recorder: Address,
+        player: Address,
+        achievement_id: u32,
+    ) -> bool {
+        recorder.require_auth();
+        if !Self::is_recorder(env.clone(), recorder) {
+            panic!("not a recorder");
+        }
+        let completed = Self::record_internal(&env, &player, achievement_id);
+        Self::store(
+            &env,
+            &DataKey::RecorderBacked(player, achievement_id),
+            &true,
+        );
+        completed
+    }
+
+    // Record progress by proving ownership of the matching achievement NFT
+    pub fn record_with_nft(env: Env, player: Address, token_id: u32) -> bool {
+        player.require_auth();
+        let nft_contract = Self::get_nft_contract(env.clone()).expect("nft contract");
+
+        let owner: Address = env.invoke_contract(
+            &nft_contract,
+            &Symbol::new(&env, "owner_of"),
+            (token_id,).into_val(&env),
+        );
+        if owner != player {
+            panic!("not token owner");
+        }
+        let token = Self::nft_achievement(&env, &nft_contract, token_id).expect("token");
+
+        match env
+            .storage()
+            .persistent()
+            .get::<DataKey, TokenProgress>(&DataKey::TokenProgress(token_id))
+        {
+            // Recorded by a previous owner and not synced yet: the progress moves over
+            // instead of being credited twice
+            Some(record) if record.holder != player => {
+                Self::move_token_progress(&env, token_id, record, &player);
+                return Self::award_if_completed(&env, &player, token.puzzle_id, Some(token_id));
+            }
+            Some(_) => {}
+            None => {
+                // Progress held without any backing predates NFT proof and stays with the player
+                if Self::holds(&env, &player, token.puzzle_id)
+                    && Self::token_backing(&env, &player, token.puzzle_id) == 0
+                {
+                    Self::store(
+                        &env,
+                        &DataKey::RecorderBacked(player.clone(), token.puzzle_id),
+                        &true,
+                    );
+                }
+                let record = TokenProgress {
+                    holder: player.clone(),
+                    achievement_id: token.puzzle_id,
+                };
+                Self::store(&env, &DataKey::TokenProgress(token_id), &record);
+                Self::add_token_backing(&env, &player, token.puzzle_id);
+            }
+        }
+        Self::record_internal(&env, &player, token.puzzle_id)
+    }
+
+    // Award a completion that recording could not see: a meta set created or linked
+    // after its children were completed, or a set whose season opened later. Callable
+    // by anyone.
+    pub fn claim_completion(env: Env, player: Address, set_id: u32) -> bool {
+        Self::evaluate_set(&env, &player, set_id, None)
+    }
+
+    // Move NFT-backed progress to the token's current owner after a transfer, or drop
+    // it once the token is burned. Callable by anyone.
+    pub fn sync_nft_transfer(env: Env, token_id: u32) -> bool {
+        let nft_contract = Self::get_nft_contract(env.clone()).expect("nft contract");
+        let record: TokenProgress = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TokenProgress(token_id))
+            .expect("token not recorded");
+
+        match Self::nft_achievement(&env, &nft_contract, token_id) {
+            Some(token) => {
+                if token.owner == record.holder {
+                    return false;
+                }
+                let achievement_id = record.achievement_id;
+                Self::move_token_progress(&env, token_id, record, &token.owner);
+                Self::award_if_completed(&env, &token.owner, achievement_id, Some(token_id));
+            }
+            None => {
+                Self::release_token_backing(&env, &record.holder, record.achievement_id);
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::TokenProgress(token_id));
+                env.storage()
+                    .persistent()
+                    .remove(&DataKey::TokenPaidSets(token_id));
+            }
+        }
+        true
+    }
+
+    fn move_token_progress(env: &Env, token_id: u32, mut record: TokenProgress, to: &Address) {
+        for set_id in Self::sets_of(env, record.achievement_id).iter() {
+            Self::mark_token_paid(env, token_id, &record.holder, set_id);
+        }
+        Self::release_token_backing(env, &record.holder, record.achievement_id);
+        Self::add_token_backing(env, to, record.achievement_id);
+        Self::add_progress(env, to, record.achievement_id);
+        record.holder = to.clone();
+        Self::store(env, &DataKey::TokenProgress(token_id), &record);
+    }
+
+    // A token leaving a holder who completed a set has earned that set's bonus, and the
+    // bonus of every completed meta set above it
+    fn mark_token_paid(env: &Env, token_id: u32, holder: &Address, set_id: u32) {
+        if !Self::has_completed(env, holder, set_id) {
+            return;
+        }
+        let mut paid = Self::token_paid_sets(env, token_id);
+        if !paid.contains(set_id) {
+            paid.push_back(set_id);
+            Self::store(env, &DataKey::TokenPaidSets(token_id), &paid);
+        }
+        for parent_id in Self::parents_of(env, set_id).iter() {
+            Self::mark_token_paid(env, token_id, holder, parent_id);
+        }
+    }
+
+    fn token_paid_sets(env: &Env, token_id: u32) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TokenPaidSets(token_id))
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(key, 100_000, 500_000);
+    }
+
+    fn token_backing(env: &Env, player: &Address, achievement_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TokenBacking(player.clone(), achievement_id))
+            .unwrap_or(0)
+    }
+
+    fn add_token_backing(env: &Env, player: &Address, achievement_id: u32) {
+        let count = Self::token_backing(env, player, achievement_id) + 1;
+        Self::store(
+            env,
+            &DataKey::TokenBacking(player.clone(), achievement_id),
+            &count,
+        );
+    }
+
+    // Drop one token's backing; the progress itself only goes once neither a recorder
+    // nor another recorded token backs it
+    fn release_token_backing(env: &Env, player: &Address, achievement_id: u32) {
+        let key = DataKey::TokenBacking(player.clone(), achievement_id);
+        let count = Self::token_backing(env, player, achievement_id);
+        if count > 1 {
+            Self::store(env, &key, &(count - 1));
+            return;
+        }
+        env.storage().persistent().remove(&key);
+        let recorded: bool = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RecorderBacked(player.clone(), achievement_id))
+            .unwrap_or(false);
+        if !recorded {
+            Self::remove_progress(env, player, achievement_id);
+        }
+    }
+
+    fn record_internal(env: &Env, player: &Address, achievement_id: u32) -> bool {
+        let sets = Self::sets_of(env, achievement_id);
+        if sets.is_empty() {
+            panic!("mapped");
+        }
+
+        // progress only lands in sets whose season is open
+        let mut open = Vec::new(env);
+        for set_id in sets.iter() {
+            if Self::window_open(env, set_id) {
+                open.push_back(set_id);
+            }
+        }
+        if open.is_empty() {
+            panic!("set not open");
+        }
+
+        // limited edition check per achievement (only when acquiring new); the
+        // claim count is global, so the tightest cap among its sets applies
+        if !Self::holds(env, player, achievement_id) {
+            let mut cap: Option<u32> = None;
+            for set_id in open.iter() {
+                if let Some(set_cap) = Self::load_set(env, set_id).limited_cap {
+                    cap = Some(cap.map_or(set_cap, |c| c.min(set_cap)));
+                }
+            }
+            if let Some(cap) = cap {
+                let mut cnt: u32 = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::AchCount(achievement_id))
+                    .unwrap_or(0);
                 if cnt >= cap {
                     panic!("limited cap reached");
                 }
@@ -147,21 +657,105 @@ impl AchievementCollection {
             }
         }
 
-        let mut progress: Vec<u32> = env
-            .storage()
+        for set_id in open.iter() {
+            Self::add_to_set(env, player, set_id, achievement_id);
+        }
+
+        // completion detection
+        Self::award_if_completed(env, player, achievement_id, None)
+    }
+
+    fn load_set(env: &Env, set_id: u32) -> Set {
+        env.storage()
+            .instance()
+            .get(&DataKey::Set(set_id))
+            .expect("set")
+    }
+
+    // Whether `set_id` transitively requires `target`
+    fn requires_set(env: &Env, set_id: u32, target: u32) -> bool {
+        for child_id in Self::load_set(env, set_id).required_sets.iter() {
+            if child_id == target || Self::requires_set(env, child_id, target) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn stored_progress(env: &Env, player: &Address, set_id: u32) -> Vec<u32> {
+        env.storage()
             .instance()
             .get(&DataKey::PlayerProgress(player.clone(), set_id))
-            .unwrap_or(Vec::new(&env.clone()));
-        if !progress.contains(&achievement_id) {
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn holds(env: &Env, player: &Address, achievement_id: u32) -> bool {
+        for set_id in Self::sets_of(env, achievement_id).iter() {
+            if Self::stored_progress(env, player, set_id).contains(achievement_id) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn add_to_set(env: &Env, player: &Address, set_id: u32, achievement_id: u32) {
+        let mut progress = Self::stored_progress(env, player, set_id);
+        if !progress.contains(achievement_id) {
             progress.push_back(achievement_id);
             env.storage()
                 .instance()
                 .set(&DataKey::PlayerProgress(player.clone(), set_id), &progress);
         }
+    }
+
+    fn add_progress(env: &Env, player: &Address, achievement_id: u32) {
+        for set_id in Self::sets_of(env, achievement_id).iter() {
+            Self::add_to_set(env, player, set_id, achievement_id);
+        }
+    }
 
-        // completion detection
-        let completed = Self::is_completed_internal(&set, &progress);
-        if completed {
+    fn remove_progress(env: &Env, player: &Address, achievement_id: u32) {
+        for set_id in Self::sets_of(env, achievement_id).iter() {
+            let mut progress = Self::stored_progress(env, player, set_id);
+            if let Some(index) = progress.first_index_of(achievement_id) {
+                progress.remove(index);
+                env.storage()
+                    .instance()
+                    .set(&DataKey::PlayerProgress(player.clone(), set_id), &progress);
+            }
+        }
+    }
+
+    // `moved_token` is the NFT whose progress just moved to `player`; a completion it
+    // triggers is only rewarded if that token has not earned the set's bonus before
+    fn award_if_completed(
+        env: &Env,
+        player: &Address,
+        achievement_id: u32,
+        moved_token: Option<u32>,
+    ) -> bool {
+        let mut completed = false;
+        for set_id in Self::sets_of(env, achievement_id).iter() {
+            if Self::evaluate_set(env, player, set_id, moved_token) {
+                completed = true;
+            }
+        }
+        completed
+    }
+
+    // Award a set's bonus on first completion, then re-evaluate the meta sets requiring it.
+    // A completion carried by an already rewarded token is only marked, so re-recording
+    // that progress later cannot pay it out either.
+    fn evaluate_set(env: &Env, player: &Address, set_id: u32, moved_token: Option<u32>) -> bool {
+        let set = Self::load_set(env, set_id);
+        let completed = Self::requirements_met(env, player, &set);
+        let rewarded = !moved_token
+            .is_some_and(|token_id| Self::token_paid_sets(env, token_id).contains(set_id))
+            && !set
+                .required_sets
+                .iter()
+                .any(|child_id| Self::unrewarded(env, player, child_id));
+        if completed && (!rewarded || Self::window_open(env, set_id)) {
             // award bonus only once per player per set
             let already: bool = env
                 .storage()
@@ -169,94 +763,218 @@ impl AchievementCollection {
                 .get(&DataKey::Completed(player.clone(), set_id))
                 .unwrap_or(false);
             if !already {
-                let mut bonus: i128 = env
-                    .storage()
-                    .instance()
-                    .get(&DataKey::Bonus(player.clone()))
-                    .unwrap_or(0);
-                bonus += set.bonus_points;
-                env.storage()
-                    .instance()
-                    .set(&DataKey::Bonus(player.clone()), &bonus);
                 env.storage()
                     .instance()
                     .set(&DataKey::Completed(player.clone(), set_id), &true);
+                if rewarded {
+                    Self::note_completion(env, player, set_id);
+                    Self::pay_bonus(env, player, set.bonus_points);
+                } else {
+                    Self::store(env, &DataKey::Unrewarded(player.clone(), set_id), &true);
+                }
+                for parent_id in Self::parents_of(env, set_id).iter() {
+                    Self::evaluate_set(env, player, parent_id, moved_token);
+                }
             }
         }
         completed
     }
 
-    pub fn progress(env: Env, player: Address, set_id: u32) -> Vec<u32> {
+    fn unrewarded(env: &Env, player: &Address, set_id: u32) -> bool {
         env.storage()
-            .instance()
-            .get(&DataKey::PlayerProgress(player, set_id))
-            .unwrap_or(Vec::new(&env))
+            .persistent()
+            .get(&DataKey::Unrewarded(player.clone(), set_id))
+            .unwrap_or(false)
     }
 
-    pub fn is_completed(env: Env, player: Address, set_id: u32) -> bool {
-        let set: Set = env
+    fn window_open(env: &Env, set_id: u32) -> bool {
+        match env
             .storage()
             .instance()
-            .get(&DataKey::Set(set_id))
-            .expect("set");
-        let progress: Vec<u32> = Self::progress(env.clone(), player, set_id);
-        Self::is_completed_internal(&set, &progress)
+            .get::<DataKey, SetWindow>(&DataKey::SetWindow(set_id))
+        {
+            Some(window) => {
+                let now = env.ledger().timestamp();
+                now >= window.opens_at && now <= window.closes_at
+            }
+            None => true,
+        }
     }
 
-    fn is_completed_internal(set: &Set, progress: &Vec<u32>) -> bool {
+    fn note_completion(env: &Env, player: &Address, set_id: u32) {
+        let count = Self::completion_count(env.clone(), set_id) + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::CompletionCount(set_id), &count);
+
+        if count <= MAX_FIRST_COMPLETERS {
+            let key = DataKey::FirstCompleters(set_id);
+            let mut completers = Self::first_completers(env.clone(), set_id);
+            completers.push_back(player.clone());
+            env.storage().persistent().set(&key, &completers);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, 100_000, 500_000);
+        }
+    }
+
+    // Token payouts accrue until the player claims them, so an underfunded or paused
+    // reward token never blocks recording progress
+    fn pay_bonus(env: &Env, player: &Address, amount: i128) {
+        if amount <= 0 {
+            return;
+        }
+        match Self::get_reward_payout(env.clone()) {
+            RewardPayout::Internal => Self::credit_bonus(env, player, amount),
+            RewardPayout::Token(_) | RewardPayout::Mint(_) => {
+                let pending = Self::pending_bonus_of(env.clone(), player.clone()) + amount;
+                Self::store(env, &DataKey::PendingBonus(player.clone()), &pending);
+            }
+        }
+    }
+
+    fn credit_bonus(env: &Env, player: &Address, amount: i128) {
+        let bonus = Self::bonus_of(env.clone(), player.clone()) + amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::Bonus(player.clone()), &bonus);
+    }
+
+    fn send_bonus(env: &Env, player: &Address, amount: i128) {
+        match Self::get_reward_payout(env.clone()) {
+            RewardPayout::Internal => Self::credit_bonus(env, player, amount),
+            RewardPayout::Token(reward_token) => {
+                token::Client::new(env, &reward_token).transfer(
+                    &env.current_contract_address(),
+                    player,
+                    &amount,
+                );
+            }
+            RewardPayout::Mint(reward_token) => {
+                env.invoke_contract::<()>(
+                    &reward_token,
+                    &Symbol::new(env, "mint"),
+                    (env.current_contract_address(), player.clone(), amount).into_val(env),
+                );
+            }
+        }
+    }
+
+    fn nft_achievement(env: &Env, nft_contract: &Address, token_id: u32) -> Option<NftAchievement> {
+        env.invoke_contract(
+            nft_contract,
+            &Symbol::new(env, "get_achievement"),
+            (token_id,).into_val(env),
+        )
+    }
+
+    // Achievements collected toward a set; for meta sets this includes every
+    // achievement collected toward the sets it requires, recursively
+    pub fn progress(env: Env, player: Address, set_id: u32) -> Vec<u32> {
+        let mut collected = Self::stored_progress(&env, &player, set_id);
+        let set = Self::load_set(&env, set_id);
+        for child_id in set.required_sets.iter() {
+            for achievement_id in Self::progress(env.clone(), player.clone(), child_id).iter() {
+                if !collected.contains(achievement_id) {
+                    collected.push_back(achievement_id);
+                }
+            }
+        }
+        collected
+    }
+
+    // Per-set breakdown of a set and everything it requires, depth-first from `set_id`
+    pub fn nested_progress(env: Env, player: Address, set_id: u32) -> Vec<SetProgress> {
+        let mut out = Vec::new(&env);
+        Self::collect_progress(&env, &player, set_id, 0, &mut out);
+        out
+    }
+
+    fn collect_progress(
+        env: &Env,
+        player: &Address,
+        set_id: u32,
+        depth: u32,
+        out: &mut Vec<SetProgress>,
+    ) {
+        let set = Self::load_set(env, set_id);
+        let progress = Self::stored_progress(env, player, set_id);
+        let mut collected = 0u32;
+        for achievement_id in set.achievements.iter() {
+            if progress.contains(achievement_id) {
+                collected += 1;
+            }
+        }
+        for child_id in set.required_sets.iter() {
+            if Self::has_completed(env, player, child_id) {
+                collected += 1;
+            }
+        }
+        out.push_back(SetProgress {
+            set_id,
+            depth,
+            collected,
+            required: set.achievements.len() + set.required_sets.len(),
+            completed: Self::requirements_met(env, player, &set),
+        });
+        for child_id in set.required_sets.iter() {
+            Self::collect_progress(env, player, child_id, depth + 1, out);
+        }
+    }
+
+    pub fn is_completed(env: Env, player: Address, set_id: u32) -> bool {
+        let set = Self::load_set(&env, set_id);
+        Self::requirements_met(&env, &player, &set)
+    }
+
+    // Required sets count once their completion has been awarded, so a meta set
+    // keeps credit for seasonal sets that have since closed
+    fn requirements_met(env: &Env, player: &Address, set: &Set) -> bool {
+        let progress = Self::stored_progress(env, player, set.id);
         if progress.len() < set.achievements.len() {
             return false;
         }
         // every achievement in set must be present
         for a in set.achievements.iter() {
-            if !progress.contains(&a) {
+            if !progress.contains(a) {
+                return false;
+            }
+        }
+        for child_id in set.required_sets.iter() {
+            if !Self::has_completed(env, player, child_id) {
                 return false;
             }
         }
         true
     }
 
-    // Trading/swapping progress marks between players within a set
-    pub fn transfer_progress(env: Env, from: Address, to: Address, achievement_id: u32) {
-        from.require_auth();
-        let set_id: u32 = env
-            .storage()
+    fn has_completed(env: &Env, player: &Address, set_id: u32) -> bool {
+        env.storage()
             .instance()
-            .get(&DataKey::AchToSet(achievement_id))
-            .expect("mapped");
+            .get(&DataKey::Completed(player.clone(), set_id))
+            .unwrap_or(false)
+    }
 
-        let mut from_progress: Vec<u32> = env
-            .storage()
-            .instance()
-            .get(&DataKey::PlayerProgress(from.clone(), set_id))
-            .unwrap_or(Vec::new(&env.clone()));
-        if !from_progress.contains(&achievement_id) {
-            panic!("not owned");
+    // Trading/swapping progress marks between players; moves the achievement in every
+    // set it belongs to. Once an NFT contract is bound, progress only moves with the
+    // tokens themselves
+    pub fn transfer_progress(env: Env, from: Address, to: Address, achievement_id: u32) {
+        from.require_auth();
+        if env.storage().instance().has(&DataKey::NftContract) {
+            panic!("progress follows nft transfers");
         }
-        // remove from 'from'
-        let mut new_from = Vec::new(&env.clone());
-        for a in from_progress.iter() {
-            let v = a.clone();
-            if v != achievement_id {
-                new_from.push_back(v);
-            }
+        if Self::sets_of(&env, achievement_id).is_empty() {
+            panic!("mapped");
         }
-        env.storage()
-            .instance()
-            .set(&DataKey::PlayerProgress(from, set_id), &new_from);
-
-        // add to 'to'
-        let mut to_progress: Vec<u32> = env
-            .storage()
-            .instance()
-            .get(&DataKey::PlayerProgress(to.clone(), set_id))
-            .unwrap_or(Vec::new(&env.clone()));
-        if !to_progress.contains(&achievement_id) {
-            to_progress.push_back(achievement_id);
-            env.storage()
-                .instance()
-                .set(&DataKey::PlayerProgress(to.clone(), set_id), &to_progress);
+        if !Self::holds(&env, &from, achievement_id) {
+            panic!("not owned");
         }
+        Self::remove_progress(&env, &from, achievement_id);
+        Self::add_progress(&env, &to, achievement_id);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::RecorderBacked(from, achievement_id));
+        Self::store(&env, &DataKey::RecorderBacked(to, achievement_id), &true);
     }
 
     // Bonus querying and withdrawal accounting (internal)
@@ -266,6 +984,28 @@ impl AchievementCollection {
             .get(&DataKey::Bonus(player))
             .unwrap_or(0)
     }
+
+    // Token bonus accrued under a Token or Mint payout and not yet claimed
+    pub fn pending_bonus_of(env: Env, player: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingBonus(player))
+            .unwrap_or(0)
+    }
+
+    // Pay out the accrued bonus through the current reward payout
+    pub fn claim_bonus(env: Env, player: Address) -> i128 {
+        player.require_auth();
+        let amount = Self::pending_bonus_of(env.clone(), player.clone());
+        if amount <= 0 {
+            panic!("nothing to claim");
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingBonus(player.clone()));
+        Self::send_bonus(&env, &player, amount);
+        amount
+    }
 }
 
 #[cfg(test)]
@@ -280,7 +1020,10 @@ mod test {
         let contract_id = env.register_contract(None, AchievementCollection);
         let client = AchievementCollectionClient::new(&env, &contract_id);
 
-        client.initialize();
+        let admin = Address::generate(&env);
+        let recorder = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_recorder(&recorder);
 
         let name = String::from_str(&env, "Starter Set");
         let mut ach = Vec::new(&env);
@@ -292,20 +1035,20 @@ mod test {
         assert_eq!(set_id, 1);
 
         let user = Address::generate(&env);
-        assert_eq!(client.is_completed(&user, &set_id), false);
+        assert!(!client.is_completed(&user, &set_id));
 
         // record achievements
-        assert_eq!(client.record_achievement(&user, &1), false);
-        assert_eq!(client.record_achievement(&user, &2), false);
+        assert!(!client.record_achievement(&recorder, &user, &1));
+        assert!(!client.record_achievement(&recorder, &user, &2));
         // completion on third
-        assert_eq!(client.record_achievement(&user, &3), true);
+        assert!(client.record_achievement(&recorder, &user, &3));
 
         let progress = client.progress(&user, &set_id);
         assert_eq!(progress.len(), 3);
         assert_eq!(client.bonus_of(&user), 100);
 
         // re-recording an already owned achievement should not add bonus again
-        assert_eq!(client.record_achievement(&user, &3), true);
+        assert!(client.record_achievement(&recorder, &user, &3));
         assert_eq!(client.bonus_of(&user), 100);
     }
 
@@ -316,7 +1059,10 @@ mod test {
         let contract_id = env.register_contract(None, AchievementCollection);
         let client = AchievementCollectionClient::new(&env, &contract_id);
 
-        client.initialize();
+        let admin = Address::generate(&env);
+        let recorder = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_recorder(&recorder);
         let name = String::from_str(&env, "Limited Set");
         let mut ach = Vec::new(&env);
         ach.push_back(10);
@@ -329,7 +1075,7 @@ mod test {
         let b = Address::generate(&env);
 
         // A claims 10
-        client.record_achievement(&a, &10);
+        client.record_achievement(&recorder, &a, &10);
         // B cannot claim 10 due to cap; but can receive via transfer
         // A transfers 10 to B
         client.transfer_progress(&a, &b, &10);
@@ -340,6 +1086,705 @@ mod test {
         assert_eq!(bp.len(), 1);
 
         // B tries to claim again 10 - already has it, no change
-        assert_eq!(client.record_achievement(&b, &10), false);
+        assert!(!client.record_achievement(&recorder, &b, &10));
+    }
+
+    #[test]
+    #[should_panic(expected = "not a recorder")]
+    fn test_record_requires_recorder() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, AchievementCollection);
+        let client = AchievementCollectionClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+        let mut ach = Vec::new(&env);
+        ach.push_back(1);
+        client.create_set(
+            &String::from_str(&env, "Solo"),
+            &ach,
+            &Rarity::Common,
+            &None,
+            &10,
+        );
+
+        let player = Address::generate(&env);
+        client.record_achievement(&player, &player, &1);
+    }
+
+    mod nft_binding {
+        use super::*;
+        use achievement_nft::{AchievementNFT, AchievementNFTClient};
+
+        fn setup(
+            env: &Env,
+        ) -> (
+            AchievementCollectionClient<'_>,
+            AchievementNFTClient<'_>,
+            Address,
+            u32,
+        ) {
+            let nft_id = env.register_contract(None, AchievementNFT);
+            let nft = AchievementNFTClient::new(env, &nft_id);
+            let contract_id = env.register_contract(None, AchievementCollection);
+            let client = AchievementCollectionClient::new(env, &contract_id);
+
+            let admin = Address::generate(env);
+            nft.initialize(&admin);
+            client.initialize(&admin);
+            client.set_nft_contract(&nft_id);
+
+            // Achievement ids are puzzle ids
+            let mut ach = Vec::new(env);
+            ach.push_back(42);
+            ach.push_back(43);
+            let set_id = client.create_set(
+                &String::from_str(env, "Pair"),
+                &ach,
+                &Rarity::Epic,
+                &None,
+                &25,
+            );
+            (client, nft, admin, set_id)
+        }
+
+        #[test]
+        fn test_progress_follows_token() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, nft, admin, set_id) = setup(&env);
+
+            let alice = Address::generate(&env);
+            let bob = Address::generate(&env);
+            let metadata = String::from_str(&env, "achievement");
+            let t42 = nft.craftmint(&admin, &alice, &42u32, &metadata);
+            let t43 = nft.craftmint(&admin, &bob, &43u32, &metadata);
+
+            assert!(!client.record_with_nft(&alice, &t42));
+            assert!(!client.record_with_nft(&bob, &t43));
+
+            // Nothing moved yet
+            assert!(!client.sync_nft_transfer(&t42));
+
+            // Alice sells her token to Bob, who now completes the set
+            nft.transfer(&alice, &bob, &t42);
+            assert!(client.sync_nft_transfer(&t42));
+            assert_eq!(client.progress(&alice, &set_id).len(), 0);
+            assert!(client.is_completed(&bob, &set_id));
+            assert_eq!(client.bonus_of(&bob), 25);
+
+            // Burning drops the progress it backed
+            nft.burn(&t43);
+            client.sync_nft_transfer(&t43);
+            assert_eq!(client.progress(&bob, &set_id).len(), 1);
+        }
+
+        #[test]
+        fn test_rewarded_tokens_do_not_pay_again() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, nft, admin, set_id) = setup(&env);
+
+            let alice = Address::generate(&env);
+            let metadata = String::from_str(&env, "achievement");
+            let t42 = nft.craftmint(&admin, &alice, &42u32, &metadata);
+            let t43 = nft.craftmint(&admin, &alice, &43u32, &metadata);
+            client.record_with_nft(&alice, &t42);
+            assert!(client.record_with_nft(&alice, &t43));
+            assert_eq!(client.bonus_of(&alice), 25);
+
+            // Passing the same tokens through fresh accounts completes the set
+            // without paying or counting it again
+            let mut holder = alice;
+            for _ in 0..3 {
+                let next = Address::generate(&env);
+                nft.transfer(&holder, &next, &t42);
+                nft.transfer(&holder, &next, &t43);
+                client.record_with_nft(&next, &t42);
+                assert!(client.sync_nft_transfer(&t43));
+                assert!(client.is_completed(&next, &set_id));
+                assert!(client.record_with_nft(&next, &t43));
+                assert_eq!(client.bonus_of(&next), 0);
+                holder = next;
+            }
+            assert_eq!(client.completion_count(&set_id), 1);
+            assert_eq!(client.first_completers(&set_id).len(), 1);
+
+            // Nor does a collector set added on top of the unrewarded completion
+            let collector = client.create_meta_set(
+                &String::from_str(&env, "Collector"),
+                &Vec::new(&env),
+                &Vec::from_array(&env, [set_id]),
+                &Rarity::Legendary,
+                &100,
+            );
+            assert!(client.claim_completion(&holder, &collector));
+            assert_eq!(client.bonus_of(&holder), 0);
+            assert_eq!(client.completion_count(&collector), 0);
+        }
+
+        #[test]
+        #[should_panic(expected = "not token owner")]
+        fn test_record_with_someone_elses_token() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, nft, admin, _set_id) = setup(&env);
+
+            let alice = Address::generate(&env);
+            let mallory = Address::generate(&env);
+            let token = nft.craftmint(
+                &admin,
+                &alice,
+                &42u32,
+                &String::from_str(&env, "achievement"),
+            );
+
+            client.record_with_nft(&mallory, &token);
+        }
+
+        #[test]
+        #[should_panic(expected = "progress follows nft transfers")]
+        fn test_manual_transfer_disabled_when_bound() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, nft, admin, _set_id) = setup(&env);
+
+            let alice = Address::generate(&env);
+            let bob = Address::generate(&env);
+            let token = nft.craftmint(
+                &admin,
+                &alice,
+                &42u32,
+                &String::from_str(&env, "achievement"),
+            );
+            client.record_with_nft(&alice, &token);
+
+            client.transfer_progress(&alice, &bob, &42);
+        }
+
+        #[test]
+        fn test_record_transferred_token_before_sync() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, nft, admin, set_id) = setup(&env);
+
+            let alice = Address::generate(&env);
+            let bob = Address::generate(&env);
+            let token = nft.craftmint(
+                &admin,
+                &alice,
+                &42u32,
+                &String::from_str(&env, "achievement"),
+            );
+            client.record_with_nft(&alice, &token);
+
+            // Bob records the token he bought before anyone synced the transfer
+            nft.transfer(&alice, &bob, &token);
+            client.record_with_nft(&bob, &token);
+            assert_eq!(client.progress(&alice, &set_id).len(), 0);
+            assert_eq!(client.progress(&bob, &set_id).len(), 1);
+            assert!(!client.sync_nft_transfer(&token));
+
+            // The record now follows Bob, so passing it on moves his progress
+            nft.transfer(&bob, &alice, &token);
+            assert!(client.sync_nft_transfer(&token));
+            assert_eq!(client.progress(&bob, &set_id).len(), 0);
+            assert_eq!(client.progress(&alice, &set_id).len(), 1);
+        }
+
+        #[test]
+        fn test_sync_keeps_otherwise_backed_progress() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, nft, admin, set_id) = setup(&env);
+
+            let recorder = Address::generate(&env);
+            client.add_recorder(&recorder);
+            let alice = Address::generate(&env);
+            let bob = Address::generate(&env);
+            let carol = Address::generate(&env);
+            let metadata = String::from_str(&env, "achievement");
+
+            // Alice holds two recorded tokens of the same puzzle
+            let first = nft.craftmint(&admin, &alice, &42u32, &metadata);
+            let second = nft.craftmint(&admin, &alice, &42u32, &metadata);
+            client.record_with_nft(&alice, &first);
+            client.record_with_nft(&alice, &second);
+
+            nft.transfer(&alice, &bob, &first);
+            client.sync_nft_transfer(&first);
+            assert_eq!(client.progress(&alice, &set_id).len(), 1);
+            assert_eq!(client.progress(&bob, &set_id).len(), 1);
+
+            nft.transfer(&alice, &bob, &second);
+            client.sync_nft_transfer(&second);
+            assert_eq!(client.progress(&alice, &set_id).len(), 0);
+
+            // Carol's recorder credit survives selling the token she also recorded
+            client.record_achievement(&recorder, &carol, &43);
+            let third = nft.craftmint(&admin, &carol, &43u32, &metadata);
+            client.record_with_nft(&carol, &third);
+            nft.transfer(&carol, &bob, &third);
+            client.sync_nft_transfer(&third);
+            assert_eq!(client.progress(&carol, &set_id).len(), 1);
+            assert!(client.is_completed(&bob, &set_id));
+        }
+    }
+
+    mod seasons_and_payouts {
+        use super::*;
+        use reward_token::{RewardToken, RewardTokenClient};
+        use soroban_sdk::testutils::Ledger as _;
+
+        fn setup(env: &Env) -> (AchievementCollectionClient<'_>, Address, u32) {
+            let contract_id = env.register_contract(None, AchievementCollection);
+            let client = AchievementCollectionClient::new(env, &contract_id);
+
+            let admin = Address::generate(env);
+            let recorder = Address::generate(env);
+            client.initialize(&admin);
+            client.add_recorder(&recorder);
+
+            let mut ach = Vec::new(env);
+            ach.push_back(1);
+            ach.push_back(2);
+            let set_id = client.create_set(
+                &String::from_str(env, "Winter"),
+                &ach,
+                &Rarity::Legendary,
+                &None,
+                &500,
+            );
+            client.set_season_window(&set_id, &1_000, &2_000);
+            (client, recorder, set_id)
+        }
+
+        #[test]
+        fn test_sep41_payout_and_first_completers() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, recorder, set_id) = setup(&env);
+
+            let issuer = Address::generate(&env);
+            let asset = env.register_stellar_asset_contract_v2(issuer).address();
+            soroban_sdk::token::StellarAssetClient::new(&env, &asset)
+                .mint(&client.address, &10_000);
+            client.set_reward_payout(&RewardPayout::Token(asset.clone()));
+
+            let first = Address::generate(&env);
+            let second = Address::generate(&env);
+            env.ledger().set_timestamp(1_500);
+            for player in [&first, &second] {
+                client.record_achievement(&recorder, player, &1);
+                assert!(client.record_achievement(&recorder, player, &2));
+            }
+
+            let balance = soroban_sdk::token::Client::new(&env, &asset);
+            assert_eq!(client.pending_bonus_of(&first), 500);
+            assert_eq!(client.claim_bonus(&first), 500);
+            assert_eq!(client.claim_bonus(&second), 500);
+            assert_eq!(balance.balance(&first), 500);
+            assert_eq!(balance.balance(&second), 500);
+            assert_eq!(client.pending_bonus_of(&first), 0);
+            assert_eq!(client.bonus_of(&first), 0);
+            assert_eq!(client.completion_count(&set_id), 2);
+            let completers = client.first_completers(&set_id);
+            assert_eq!(completers.get(0).unwrap(), first);
+            assert_eq!(completers.get(1).unwrap(), second);
+        }
+
+        #[test]
+        fn test_reward_token_mint_payout() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, recorder, _set_id) = setup(&env);
+
+            let token_admin = Address::generate(&env);
+            let token_id = env.register_contract(None, RewardToken);
+            let reward = RewardTokenClient::new(&env, &token_id);
+            reward.initialize(
+                &token_admin,
+                &String::from_str(&env, "Reward"),
+                &String::from_str(&env, "RWD"),
+                &7,
+            );
+            reward.authorize_minter(&client.address);
+            client.set_reward_payout(&RewardPayout::Mint(token_id));
+
+            let player = Address::generate(&env);
+            env.ledger().set_timestamp(1_000);
+            client.record_achievement(&recorder, &player, &1);
+            client.record_achievement(&recorder, &player, &2);
+            client.claim_bonus(&player);
+            assert_eq!(reward.balance(&player), 500);
+        }
+
+        #[test]
+        fn test_underfunded_payout_accrues_until_claimed() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, recorder, set_id) = setup(&env);
+
+            let issuer = Address::generate(&env);
+            let asset = env.register_stellar_asset_contract_v2(issuer).address();
+            client.set_reward_payout(&RewardPayout::Token(asset.clone()));
+
+            // Completing with an empty reward pool still records the completion
+            let player = Address::generate(&env);
+            env.ledger().set_timestamp(1_500);
+            client.record_achievement(&recorder, &player, &1);
+            assert!(client.record_achievement(&recorder, &player, &2));
+            assert!(client.is_completed(&player, &set_id));
+            assert_eq!(client.pending_bonus_of(&player), 500);
+
+            soroban_sdk::token::StellarAssetClient::new(&env, &asset).mint(&client.address, &500);
+            client.claim_bonus(&player);
+            let balance = soroban_sdk::token::Client::new(&env, &asset);
+            assert_eq!(balance.balance(&player), 500);
+        }
+
+        #[test]
+        #[should_panic(expected = "nothing to claim")]
+        fn test_claim_without_bonus() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, _recorder, _set_id) = setup(&env);
+
+            client.claim_bonus(&Address::generate(&env));
+        }
+
+        #[test]
+        fn test_claim_after_parent_season_opens() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, recorder, set_id) = setup(&env);
+
+            let finale = client.create_meta_set(
+                &String::from_str(&env, "Finale"),
+                &Vec::new(&env),
+                &Vec::from_array(&env, [set_id]),
+                &Rarity::Legendary,
+                &70,
+            );
+            client.set_season_window(&finale, &3_000, &4_000);
+
+            let player = Address::generate(&env);
+            env.ledger().set_timestamp(1_500);
+            client.record_achievement(&recorder, &player, &1);
+            client.record_achievement(&recorder, &player, &2);
+            assert_eq!(client.bonus_of(&player), 500);
+            assert_eq!(client.completion_count(&finale), 0);
+
+            env.ledger().set_timestamp(3_500);
+            assert!(client.claim_completion(&player, &finale));
+            assert_eq!(client.bonus_of(&player), 570);
+            assert_eq!(client.completion_count(&finale), 1);
+        }
+
+        #[test]
+        #[should_panic(expected = "set not open")]
+        fn test_closed_season_rejects_progress() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, recorder, _set_id) = setup(&env);
+
+            let player = Address::generate(&env);
+            env.ledger().set_timestamp(2_001);
+            client.record_achievement(&recorder, &player, &1);
+        }
+    }
+
+    mod meta_sets {
+        use super::*;
+
+        fn setup(env: &Env) -> (AchievementCollectionClient<'_>, Address) {
+            let contract_id = env.register_contract(None, AchievementCollection);
+            let client = AchievementCollectionClient::new(env, &contract_id);
+            let admin = Address::generate(env);
+            let recorder = Address::generate(env);
+            client.initialize(&admin);
+            client.add_recorder(&recorder);
+            (client, recorder)
+        }
+
+        fn ids(env: &Env, values: &[u32]) -> Vec<u32> {
+            let mut out = Vec::new(env);
+            for v in values {
+                out.push_back(*v);
+            }
+            out
+        }
+
+        #[test]
+        fn test_shared_achievement_and_collector_set() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, recorder) = setup(&env);
+
+            // achievement 2 counts toward both sets
+            let forest = client.create_set(
+                &String::from_str(&env, "Forest"),
+                &ids(&env, &[1, 2]),
+                &Rarity::Common,
+                &None,
+                &10,
+            );
+            let desert = client.create_set(
+                &String::from_str(&env, "Desert"),
+                &ids(&env, &[2, 3]),
+                &Rarity::Common,
+                &None,
+                &20,
+            );
+            let collector = client.create_meta_set(
+                &String::from_str(&env, "Collector"),
+                &Vec::new(&env),
+                &ids(&env, &[forest, desert]),
+                &Rarity::Legendary,
+                &100,
+            );
+            assert_eq!(client.get_set(&collector).unwrap().required_sets.len(), 2);
+
+            let player = Address::generate(&env);
+            assert!(!client.record_achievement(&recorder, &player, &2));
+            assert_eq!(client.progress(&player, &forest).len(), 1);
+            assert_eq!(client.progress(&player, &desert).len(), 1);
+
+            assert!(client.record_achievement(&recorder, &player, &1));
+            assert!(!client.is_completed(&player, &collector));
+            assert_eq!(client.bonus_of(&player), 10);
+
+            // completing the second child cascades into the collector set
+            assert!(client.record_achievement(&recorder, &player, &3));
+            assert!(client.is_completed(&player, &collector));
+            assert_eq!(client.bonus_of(&player), 130);
+            assert_eq!(client.completion_count(&collector), 1);
+            assert_eq!(client.progress(&player, &collector).len(), 3);
+        }
+
+        #[test]
+        fn test_claim_meta_set_created_after_children() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, recorder) = setup(&env);
+
+            let forest = client.create_set(
+                &String::from_str(&env, "Forest"),
+                &ids(&env, &[1]),
+                &Rarity::Common,
+                &None,
+                &10,
+            );
+            let desert = client.create_set(
+                &String::from_str(&env, "Desert"),
+                &ids(&env, &[2]),
+                &Rarity::Common,
+                &None,
+                &20,
+            );
+            let player = Address::generate(&env);
+            client.record_achievement(&recorder, &player, &1);
+            client.record_achievement(&recorder, &player, &2);
+
+            let collector = client.create_meta_set(
+                &String::from_str(&env, "Collector"),
+                &Vec::new(&env),
+                &ids(&env, &[forest, desert]),
+                &Rarity::Legendary,
+                &100,
+            );
+            assert!(client.is_completed(&player, &collector));
+            assert_eq!(client.bonus_of(&player), 30);
+
+            assert!(client.claim_completion(&player, &collector));
+            assert_eq!(client.bonus_of(&player), 130);
+            assert_eq!(client.completion_count(&collector), 1);
+
+            // Claiming again pays nothing
+            client.claim_completion(&player, &collector);
+            assert_eq!(client.bonus_of(&player), 130);
+
+            // Nothing to award for a player who has not completed the children
+            let newcomer = Address::generate(&env);
+            assert!(!client.claim_completion(&newcomer, &collector));
+            assert_eq!(client.completion_count(&collector), 1);
+        }
+
+        #[test]
+        fn test_nested_progress() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, recorder) = setup(&env);
+
+            let a = client.create_set(
+                &String::from_str(&env, "A"),
+                &ids(&env, &[1, 2]),
+                &Rarity::Common,
+                &None,
+                &0,
+            );
+            let b = client.create_meta_set(
+                &String::from_str(&env, "B"),
+                &ids(&env, &[3]),
+                &ids(&env, &[a]),
+                &Rarity::Rare,
+                &0,
+            );
+            let c = client.create_meta_set(
+                &String::from_str(&env, "C"),
+                &Vec::new(&env),
+                &ids(&env, &[b]),
+                &Rarity::Epic,
+                &0,
+            );
+
+            let player = Address::generate(&env);
+            client.record_achievement(&recorder, &player, &1);
+            client.record_achievement(&recorder, &player, &3);
+
+            let nested = client.nested_progress(&player, &c);
+            assert_eq!(nested.len(), 3);
+            assert_eq!(
+                nested.get(0).unwrap(),
+                SetProgress {
+                    set_id: c,
+                    depth: 0,
+                    collected: 0,
+                    required: 1,
+                    completed: false,
+                }
+            );
+            assert_eq!(
+                nested.get(1).unwrap(),
+                SetProgress {
+                    set_id: b,
+                    depth: 1,
+                    collected: 1,
+                    required: 2,
+                    completed: false,
+                }
+            );
+            assert_eq!(
+                nested.get(2).unwrap(),
+                SetProgress {
+                    set_id: a,
+                    depth: 2,
+                    collected: 1,
+                    required: 2,
+                    completed: false,
+                }
+            );
+
+            client.record_achievement(&recorder, &player, &2);
+            assert!(client.is_completed(&player, &c));
+            let nested = client.nested_progress(&player, &c);
+            assert_eq!(nested.get(0).unwrap().collected, 1);
+        }
+
+        #[test]
+        #[should_panic(expected = "cycle")]
+        fn test_cycle_rejected() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, _recorder) = setup(&env);
+
+            let a = client.create_set(
+                &String::from_str(&env, "A"),
+                &ids(&env, &[1]),
+                &Rarity::Common,
+                &None,
+                &0,
+            );
+            let b = client.create_meta_set(
+                &String::from_str(&env, "B"),
+                &Vec::new(&env),
+                &ids(&env, &[a]),
+                &Rarity::Rare,
+                &0,
+            );
+            let c = client.create_meta_set(
+                &String::from_str(&env, "C"),
+                &Vec::new(&env),
+                &ids(&env, &[b]),
+                &Rarity::Epic,
+                &0,
+            );
+            client.add_set_to_set(&a, &c);
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_set_management_requires_admin() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let (client, _recorder) = setup(&env);
+            let a = client.create_set(
+                &String::from_str(&env, "A"),
+                &ids(&env, &[1]),
+                &Rarity::Common,
+                &None,
+                &0,
+            );
+
+            env.set_auths(&[]);
+            client.add_achievement_to_set(&a, &2);
+        }
+
+        #[test]
+        fn test_migrate_legacy_sets() {
+            let env = Env::default();
+            env.mock_all_auths();
+            let contract_id = env.register_contract(None, AchievementCollection);
+            let client = AchievementCollectionClient::new(&env, &contract_id);
+            let player = Address::generate(&env);
+
+            // Storage as written by the release before admins and meta sets
+            env.as_contract(&contract_id, || {
+                let legacy = LegacySet {
+                    id: 1,
+                    name: String::from_str(&env, "Starter"),
+                    achievements: ids(&env, &[1, 2]),
+                    rarity: Rarity::Common,
+                    limited_cap: None,
+                    bonus_points: 10,
+                };
+                let storage = env.storage().instance();
+                storage.set(&DataKey::Set(1), &legacy);
+                storage.set(&DataKey::AchToSet(1), &1u32);
+                storage.set(&DataKey::AchToSet(2), &1u32);
+                storage.set(&DataKey::NextSetId, &2u32);
+                storage.set(
+                    &DataKey::PlayerProgress(player.clone(), 1),
+                    &ids(&env, &[1]),
+                );
+            });
+
+            let admin = Address::generate(&env);
+            client.initialize(&admin);
+            let recorder = Address::generate(&env);
+            client.add_recorder(&recorder);
+
+            assert_eq!(client.migrate_sets(&1, &10), 1);
+            assert_eq!(client.migrate_sets(&1, &10), 0);
+            let set = client.get_set(&1).unwrap();
+            assert_eq!(set.achievements, ids(&env, &[1, 2]));
+            assert!(set.required_sets.is_empty());
+            env.as_contract(&contract_id, || {
+                assert!(!env.storage().instance().has(&DataKey::AchToSet(1)));
+            });
+
+            // Existing progress carries over and the set still completes
+            assert!(client.record_achievement(&recorder, &player, &2));
+            assert_eq!(client.bonus_of(&player), 10);
+            let next = client.create_set(
+                &String::from_str(&env, "Next"),
+                &ids(&env, &[3]),
+                &Rarity::Rare,
+                &None,
+                &0,
+            );
+            assert_eq!(next, 2);
+        }
     }
 }
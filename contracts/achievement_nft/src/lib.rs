@@ -1,12 +1,8 @@
 #![no_std]
-<<<<<<< Updated upstream
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Env, String, Vec,
+    contract, contractimpl, contracttype, symbol_short, Address, Env, IntoVal, String,
+    Symbol, TryFromVal, Val, Vec,
 };
-=======
-
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String};
->>>>>>> Stashed changes
 
 #[contracttype]
 #[derive(Clone)]
@@ -25,8 +21,32 @@ pub enum DataKey {
     TotalSupply,               // Instance: Current count of NFTs
     Admin,                     // Instance: Contract administrator
     PuzzleCompleted(Address, u32), // Tracks if a user has completed a puzzle
+    Approved(u32),             // Persistent: Single-token approval
+    Operator(Address, Address), // Persistent: (owner, operator) approval for all tokens
+    Name,                      // Instance: Collection name
+    Symbol,                    // Instance: Collection symbol
+    BaseUri,                   // Instance: Prefix for token URIs
+    SoulboundPuzzle(u32),      // Persistent: Tokens minted for this puzzle are soulbound
+    SoulboundToken(u32),       // Persistent: Token cannot be transferred
+    Verifier,                  // Instance: puzzle_verification contract consulted on mint
+    Minted(Address, u32),      // Persistent: Player already minted for this puzzle
+    EditionCount(u32),         // Persistent: Editions minted per puzzle
+    Edition(u32),              // Persistent: Edition number of a token
+    PuzzleTokenCount(u32),     // Persistent: Live tokens minted for a puzzle
+    PuzzleToken(u32, u32),     // Persistent: (puzzle, index) -> live token ID
+    PuzzleTokenIndex(u32),     // Persistent: Index of a token in its puzzle's token list
+    PuzzleHolderCount(u32),    // Persistent: Distinct current holders of a puzzle's tokens
+    PuzzleHolder(u32, u32),    // Persistent: (puzzle, index) -> holder
+    PuzzleHolderIndex(u32, Address), // Persistent: Index of a holder in the puzzle's holder list
+    HolderBalance(u32, Address), // Persistent: Tokens of a puzzle held by an address
+    Crafter(Address),          // Instance: Crafting contract allowed to call craftmint
 }
 
+const MAX_URI_LEN: usize = 256;
+// Room left in a token URI for the longest u32 token id
+const MAX_BASE_URI_LEN: usize = MAX_URI_LEN - 10;
+const MAX_PAGE_SIZE: u32 = 100;
+
 #[contract]
 pub struct AchievementNFT;
 
@@ -42,8 +62,32 @@ impl AchievementNFT {
         env.storage().instance().set(&DataKey::TotalSupply, &0u32);
     }
 
-<<<<<<< Updated upstream
-    /// Admin function to mark a puzzle as completed for a user.
+    /// Admin function to set the collection name, symbol and token URI prefix.
+    pub fn set_metadata(env: Env, name: String, symbol: String, base_uri: String) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        if base_uri.len() as usize > MAX_BASE_URI_LEN {
+            panic!("Base URI too long");
+        }
+        env.storage().instance().set(&DataKey::Name, &name);
+        env.storage().instance().set(&DataKey::Symbol, &symbol);
+        env.storage().instance().set(&DataKey::BaseUri, &base_uri);
+    }
+
+    /// Admin function to set the puzzle_verification contract that `mint` checks.
+    pub fn set_verifier(env: Env, verifier: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Verifier, &verifier);
+    }
+
+    /// Returns the configured puzzle_verification contract, if any.
+    pub fn get_verifier(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Verifier)
+    }
+
+    /// Admin override marking a puzzle as completed for a user, for legacy completions
+    /// the verifier has no record of.
     pub fn mark_puzzle_completed(env: Env, user: Address, puzzle_id: u32) {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
@@ -51,18 +95,97 @@ impl AchievementNFT {
             .persistent()
             .set(&DataKey::PuzzleCompleted(user, puzzle_id), &true);
     }
-=======
-    /// Mint a new achievement NFT
+
+    /// Mint a new achievement NFT for a completed puzzle. Completion is checked against
+    /// the verifier, falling back to admin overrides. One mint per player per puzzle.
     pub fn mint(env: Env, to: Address, puzzle_id: u32, metadata: String) -> u32 {
         to.require_auth();
->>>>>>> Stashed changes
 
-    /// Mint a new NFT for crafting purposes (testnet: no auth required).
-    pub fn craftmint(env: Env, to: Address, puzzle_id: u32, metadata: String) -> u32 {
-        // For testnet deployment, remove admin auth requirement
-        // let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        // admin.require_auth();
+        let minted_key = DataKey::Minted(to.clone(), puzzle_id);
+        if env.storage().persistent().has(&minted_key) {
+            panic!("Already minted");
+        }
+
+        if !Self::has_completed(&env, &to, puzzle_id) {
+            panic!("Puzzle not completed");
+        }
+
+        env.storage().persistent().set(&minted_key, &true);
+        env.storage().persistent().extend_ttl(&minted_key, 100_000, 500_000);
+
+        Self::mint_token(&env, to, puzzle_id, metadata)
+    }
+
+    /// Returns whether the player has already minted the achievement for a puzzle.
+    pub fn has_minted(env: Env, player: Address, puzzle_id: u32) -> bool {
+        env.storage().persistent().has(&DataKey::Minted(player, puzzle_id))
+    }
+
+    /// Returns the edition number of a token within its puzzle, starting at 1.
+    pub fn edition_of(env: Env, token_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Edition(token_id))
+            .expect("Token does not exist")
+    }
+
+    /// Returns how many editions have been minted for a puzzle.
+    pub fn editions_minted(env: Env, puzzle_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EditionCount(puzzle_id))
+            .unwrap_or(0)
+    }
+
+    fn has_completed(env: &Env, player: &Address, puzzle_id: u32) -> bool {
+        let overridden: bool = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PuzzleCompleted(player.clone(), puzzle_id))
+            .unwrap_or(false);
+        if overridden {
+            return true;
+        }
 
+        match Self::get_verifier(env.clone()) {
+            Some(verifier) => env.invoke_contract::<bool>(
+                &verifier,
+                &Symbol::new(env, "is_completed"),
+                (player.clone(), puzzle_id).into_val(env),
+            ),
+            None => false,
+        }
+    }
+
+    /// Admin function to allow or revoke a crafting contract's use of `craftmint`.
+    pub fn set_crafter(env: Env, crafter: Address, allowed: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        if allowed {
+            env.storage().instance().set(&DataKey::Crafter(crafter), &true);
+        } else {
+            env.storage().instance().remove(&DataKey::Crafter(crafter));
+        }
+    }
+
+    /// Returns whether an address is allowed to call `craftmint`.
+    pub fn is_crafter(env: Env, crafter: Address) -> bool {
+        env.storage().instance().has(&DataKey::Crafter(crafter))
+    }
+
+    /// Mint a new NFT for crafting purposes. `minter` must be the admin or an allowed
+    /// crafting contract, and must authorize the call.
+    pub fn craftmint(env: Env, minter: Address, to: Address, puzzle_id: u32, metadata: String) -> u32 {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if minter != admin && !Self::is_crafter(env.clone(), minter.clone()) {
+            panic!("Not authorized to craft");
+        }
+        minter.require_auth();
+
+        Self::mint_token(&env, to, puzzle_id, metadata)
+    }
+
+    fn mint_token(env: &Env, to: Address, puzzle_id: u32, metadata: String) -> u32 {
         let token_id: u32 = env.storage().instance().get(&DataKey::NextTokenId).unwrap();
 
         let achievement = Achievement {
@@ -77,6 +200,25 @@ impl AchievementNFT {
         env.storage().persistent().set(&key, &achievement);
         env.storage().persistent().extend_ttl(&key, 100_000, 500_000);
 
+        // Assign the next edition for this puzzle
+        let edition_count_key = DataKey::EditionCount(puzzle_id);
+        let edition: u32 = env.storage().persistent().get(&edition_count_key).unwrap_or(0) + 1;
+        env.storage().persistent().set(&edition_count_key, &edition);
+        env.storage().persistent().extend_ttl(&edition_count_key, 100_000, 500_000);
+        let edition_key = DataKey::Edition(token_id);
+        env.storage().persistent().set(&edition_key, &edition);
+        env.storage().persistent().extend_ttl(&edition_key, 100_000, 500_000);
+
+        // Inherit the puzzle's soulbound setting at mint time
+        let soulbound_puzzle: bool = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SoulboundPuzzle(puzzle_id))
+            .unwrap_or(false);
+        if soulbound_puzzle {
+            Self::store_soulbound(env, token_id, true);
+        }
+
         // Update Owner Collection
         let mut collection = Self::get_collection(env.clone(), to.clone());
         collection.push_back(token_id);
@@ -84,6 +226,10 @@ impl AchievementNFT {
         env.storage().persistent().set(&collection_key, &collection);
         env.storage().persistent().extend_ttl(&collection_key, 100_000, 500_000);
 
+        // Update puzzle indexes
+        Self::add_puzzle_token(env, puzzle_id, token_id);
+        Self::add_holding(env, puzzle_id, &to);
+
         // Update Counters
         env.storage().instance().set(&DataKey::NextTokenId, &(token_id + 1));
         let total: u32 = env.storage().instance().get(&DataKey::TotalSupply).unwrap_or(0);
@@ -98,7 +244,219 @@ impl AchievementNFT {
     /// Transfers a token safely
     pub fn transfer(env: Env, from: Address, to: Address, token_id: u32) {
         from.require_auth();
+        Self::move_token(&env, from, to, token_id);
+    }
+
+    /// Transfers a token on the owner's behalf. The spender must be the owner, the
+    /// token's approved address, or an operator approved for all of the owner's tokens.
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u32) {
+        spender.require_auth();
+
+        let owner = Self::owner_of(env.clone(), token_id);
+        if owner != from {
+            panic!("Not the owner");
+        }
+        if !Self::is_authorized_spender(&env, &spender, &owner, token_id) {
+            panic!("Not approved");
+        }
+
+        Self::move_token(&env, from, to, token_id);
+    }
+
+    /// Approves an address to transfer a single token, or clears the approval with `None`.
+    /// The caller must be the owner or an approved operator.
+    pub fn approve(env: Env, caller: Address, approved: Option<Address>, token_id: u32) {
+        caller.require_auth();
+
+        let owner = Self::owner_of(env.clone(), token_id);
+        if caller != owner && !Self::is_approved_for_all(env.clone(), owner.clone(), caller) {
+            panic!("Not the owner or operator");
+        }
+
+        let key = DataKey::Approved(token_id);
+        match &approved {
+            Some(address) => {
+                if address == &owner {
+                    panic!("Cannot approve owner");
+                }
+                env.storage().persistent().set(&key, address);
+                env.storage().persistent().extend_ttl(&key, 100_000, 500_000);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+
+        env.events().publish((symbol_short!("approve"), owner), (approved, token_id));
+    }
 
+    /// Grants or revokes an operator's right to transfer all of the owner's tokens.
+    pub fn set_approval_for_all(env: Env, owner: Address, operator: Address, approved: bool) {
+        owner.require_auth();
+
+        if owner == operator {
+            panic!("Cannot approve self");
+        }
+
+        let key = DataKey::Operator(owner.clone(), operator.clone());
+        if approved {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, 100_000, 500_000);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events().publish((symbol_short!("appr_all"), owner, operator), approved);
+    }
+
+    /// Returns the address approved for a single token, if any.
+    pub fn get_approved(env: Env, token_id: u32) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Approved(token_id))
+    }
+
+    /// Returns whether an operator may transfer all of the owner's tokens.
+    pub fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Operator(owner, operator))
+            .unwrap_or(false)
+    }
+
+    /// Returns the collection name.
+    pub fn name(env: Env) -> String {
+        env.storage()
+            .instance()
+            .get(&DataKey::Name)
+            .unwrap_or(String::from_str(&env, "Puzzle Achievement"))
+    }
+
+    /// Returns the collection symbol.
+    pub fn symbol(env: Env) -> String {
+        env.storage()
+            .instance()
+            .get(&DataKey::Symbol)
+            .unwrap_or(String::from_str(&env, "ACHV"))
+    }
+
+    /// Returns the token URI: the base URI followed by the token ID when a base URI is
+    /// set, otherwise the token's own metadata.
+    pub fn token_uri(env: Env, token_id: u32) -> String {
+        let achievement: Achievement = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Achievement(token_id))
+            .expect("Token does not exist");
+
+        let base_uri: String = match env.storage().instance().get(&DataKey::BaseUri) {
+            Some(uri) => uri,
+            None => return achievement.metadata,
+        };
+        // `set_metadata` rejects longer prefixes; never fail the view over one
+        let base_len = base_uri.len() as usize;
+        if base_len == 0 || base_len > MAX_BASE_URI_LEN {
+            return achievement.metadata;
+        }
+        let mut buf = [0u8; MAX_URI_LEN];
+        base_uri.copy_into_slice(&mut buf[..base_len]);
+
+        let mut digits = [0u8; 10];
+        let mut n = token_id;
+        let mut count = 0;
+        loop {
+            digits[count] = b'0' + (n % 10) as u8;
+            count += 1;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+        for i in 0..count {
+            buf[base_len + i] = digits[count - 1 - i];
+        }
+
+        String::from_bytes(&env, &buf[..base_len + count])
+    }
+
+    /// Admin function to make future mints for a puzzle soulbound. Tokens already
+    /// minted keep their own flag.
+    pub fn set_puzzle_soulbound(env: Env, puzzle_id: u32, soulbound: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let key = DataKey::SoulboundPuzzle(puzzle_id);
+        if soulbound {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, 100_000, 500_000);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Admin function to set or clear the soulbound flag on a single token.
+    pub fn set_token_soulbound(env: Env, token_id: u32, soulbound: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&DataKey::Achievement(token_id)) {
+            panic!("Token does not exist");
+        }
+        Self::store_soulbound(&env, token_id, soulbound);
+    }
+
+    /// Returns whether a token is bound to its holder.
+    pub fn is_soulbound(env: Env, token_id: u32) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SoulboundToken(token_id))
+            .unwrap_or(false)
+    }
+
+    /// Moves a soulbound token to a new wallet, e.g. after a key loss. Requires the
+    /// admin and both the old and new holder to authorize.
+    pub fn recover_soulbound(env: Env, from: Address, to: Address, token_id: u32) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        from.require_auth();
+        to.require_auth();
+
+        if !Self::is_soulbound(env.clone(), token_id) {
+            panic!("Token is not soulbound");
+        }
+        if Self::owner_of(env.clone(), token_id) != from {
+            panic!("Not the owner");
+        }
+
+        Self::reassign(&env, from.clone(), to.clone(), token_id);
+
+        env.events().publish((symbol_short!("recover"), from, to), token_id);
+    }
+
+    fn store_soulbound(env: &Env, token_id: u32, soulbound: bool) {
+        let key = DataKey::SoulboundToken(token_id);
+        if soulbound {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(&key, 100_000, 500_000);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    fn is_authorized_spender(env: &Env, spender: &Address, owner: &Address, token_id: u32) -> bool {
+        if spender == owner {
+            return true;
+        }
+        if Self::get_approved(env.clone(), token_id).as_ref() == Some(spender) {
+            return true;
+        }
+        Self::is_approved_for_all(env.clone(), owner.clone(), spender.clone())
+    }
+
+    fn move_token(env: &Env, from: Address, to: Address, token_id: u32) {
+        if Self::is_soulbound(env.clone(), token_id) {
+            panic!("Token is soulbound");
+        }
+        Self::reassign(env, from, to, token_id);
+    }
+
+    fn reassign(env: &Env, from: Address, to: Address, token_id: u32) {
         if from == to {
             panic!("Cannot transfer to self");
         }
@@ -126,6 +484,13 @@ impl AchievementNFT {
         env.storage().persistent().set(&DataKey::OwnerCollection(to.clone()), &to_col);
         env.storage().persistent().extend_ttl(&DataKey::OwnerCollection(to.clone()), 100_000, 500_000);
 
+        // Move the holding between holders of this puzzle
+        Self::remove_holding(env, achievement.puzzle_id, &from);
+        Self::add_holding(env, achievement.puzzle_id, &to);
+
+        // Approvals do not survive a change of owner
+        env.storage().persistent().remove(&DataKey::Approved(token_id));
+
         // Update owner
         achievement.owner = to.clone();
         env.storage().persistent().set(&DataKey::Achievement(token_id), &achievement);
@@ -157,7 +522,8 @@ impl AchievementNFT {
         env.storage().instance().get(&DataKey::TotalSupply).unwrap_or(0)
     }
 
-    /// Destroys a token (testnet: no auth required for crafting).
+    /// Destroys a token. Requires the owner's authorization, including when a crafting
+    /// contract burns ingredients on the owner's behalf.
     pub fn burn(env: Env, token_id: u32) {
         let achievement: Achievement = env
             .storage()
@@ -165,8 +531,7 @@ impl AchievementNFT {
             .get(&DataKey::Achievement(token_id))
             .expect("Token does not exist");
 
-        // For testnet deployment, remove owner auth requirement
-        // achievement.owner.require_auth();
+        achievement.owner.require_auth();
 
         let mut collection = Self::get_collection(env.clone(), achievement.owner.clone());
         if let Some(index) = collection.first_index_of(token_id) {
@@ -174,7 +539,13 @@ impl AchievementNFT {
             env.storage().persistent().set(&DataKey::OwnerCollection(achievement.owner.clone()), &collection);
         }
 
+        Self::remove_puzzle_token(&env, achievement.puzzle_id, token_id);
+        Self::remove_holding(&env, achievement.puzzle_id, &achievement.owner);
+
         env.storage().persistent().remove(&DataKey::Achievement(token_id));
+        env.storage().persistent().remove(&DataKey::Approved(token_id));
+        env.storage().persistent().remove(&DataKey::SoulboundToken(token_id));
+        env.storage().persistent().remove(&DataKey::Edition(token_id));
         let total: u32 = env.storage().instance().get(&DataKey::TotalSupply).unwrap();
         env.storage().instance().set(&DataKey::TotalSupply, &(total - 1));
 
@@ -185,6 +556,134 @@ impl AchievementNFT {
     pub fn get_achievement(env: Env, token_id: u32) -> Option<Achievement> {
         env.storage().persistent().get(&DataKey::Achievement(token_id))
     }
+
+    /// Returns a page of the token IDs owned by an address.
+    pub fn tokens_of_owner(env: Env, owner: Address, offset: u32, limit: u32) -> Vec<u32> {
+        let collection = Self::get_collection(env.clone(), owner);
+        Self::page(&env, &collection, offset, limit)
+    }
+
+    /// Returns a page of the live token IDs minted for a puzzle.
+    pub fn tokens_of_puzzle(env: Env, puzzle_id: u32, offset: u32, limit: u32) -> Vec<u32> {
+        let count = Self::puzzle_supply(env.clone(), puzzle_id);
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+        let mut page = Vec::new(&env);
+        for i in offset..end {
+            page.push_back(Self::load(&env, &DataKey::PuzzleToken(puzzle_id, i)));
+        }
+        page
+    }
+
+    /// Returns the number of live (unburned) tokens for a puzzle.
+    pub fn puzzle_supply(env: Env, puzzle_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PuzzleTokenCount(puzzle_id))
+            .unwrap_or(0)
+    }
+
+    /// Returns a page of the distinct addresses currently holding a puzzle's tokens.
+    pub fn holders_of_puzzle(env: Env, puzzle_id: u32, offset: u32, limit: u32) -> Vec<Address> {
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PuzzleHolderCount(puzzle_id))
+            .unwrap_or(0);
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+        let mut page = Vec::new(&env);
+        for i in offset..end {
+            page.push_back(Self::load(&env, &DataKey::PuzzleHolder(puzzle_id, i)));
+        }
+        page
+    }
+
+    /// Returns how many of a puzzle's tokens an address holds.
+    pub fn holder_balance(env: Env, puzzle_id: u32, holder: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::HolderBalance(puzzle_id, holder))
+            .unwrap_or(0)
+    }
+
+    fn page(env: &Env, ids: &Vec<u32>, offset: u32, limit: u32) -> Vec<u32> {
+        let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(ids.len());
+        let mut page = Vec::new(env);
+        for i in offset..end {
+            page.push_back(ids.get(i).unwrap());
+        }
+        page
+    }
+
+    fn load<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> V {
+        env.storage().persistent().get(key).expect("Index entry missing")
+    }
+
+    fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(key, 100_000, 500_000);
+    }
+
+    // Puzzle indexes keep one entry per token and per holder, so no single entry grows
+    // with a puzzle's popularity. Removal swaps the last entry into the freed slot.
+    fn add_puzzle_token(env: &Env, puzzle_id: u32, token_id: u32) {
+        let index = Self::puzzle_supply(env.clone(), puzzle_id);
+        Self::store(env, &DataKey::PuzzleToken(puzzle_id, index), &token_id);
+        Self::store(env, &DataKey::PuzzleTokenIndex(token_id), &index);
+        Self::store(env, &DataKey::PuzzleTokenCount(puzzle_id), &(index + 1));
+    }
+
+    fn remove_puzzle_token(env: &Env, puzzle_id: u32, token_id: u32) {
+        let index: u32 = Self::load(env, &DataKey::PuzzleTokenIndex(token_id));
+        let last = Self::puzzle_supply(env.clone(), puzzle_id) - 1;
+        if index != last {
+            let moved: u32 = Self::load(env, &DataKey::PuzzleToken(puzzle_id, last));
+            Self::store(env, &DataKey::PuzzleToken(puzzle_id, index), &moved);
+            Self::store(env, &DataKey::PuzzleTokenIndex(moved), &index);
+        }
+        env.storage().persistent().remove(&DataKey::PuzzleToken(puzzle_id, last));
+        env.storage().persistent().remove(&DataKey::PuzzleTokenIndex(token_id));
+        Self::store(env, &DataKey::PuzzleTokenCount(puzzle_id), &last);
+    }
+
+    fn add_holding(env: &Env, puzzle_id: u32, holder: &Address) {
+        let balance_key = DataKey::HolderBalance(puzzle_id, holder.clone());
+        let balance: u32 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        Self::store(env, &balance_key, &(balance + 1));
+
+        if balance == 0 {
+            let count_key = DataKey::PuzzleHolderCount(puzzle_id);
+            let index: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+            Self::store(env, &DataKey::PuzzleHolder(puzzle_id, index), holder);
+            Self::store(env, &DataKey::PuzzleHolderIndex(puzzle_id, holder.clone()), &index);
+            Self::store(env, &count_key, &(index + 1));
+        }
+    }
+
+    fn remove_holding(env: &Env, puzzle_id: u32, holder: &Address) {
+        let balance_key = DataKey::HolderBalance(puzzle_id, holder.clone());
+        let balance: u32 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        if balance > 1 {
+            Self::store(env, &balance_key, &(balance - 1));
+            return;
+        }
+
+        env.storage().persistent().remove(&balance_key);
+        let index_key = DataKey::PuzzleHolderIndex(puzzle_id, holder.clone());
+        let index: u32 = match env.storage().persistent().get(&index_key) {
+            Some(index) => index,
+            None => return,
+        };
+        let count_key = DataKey::PuzzleHolderCount(puzzle_id);
+        let last: u32 = Self::load::<u32>(env, &count_key) - 1;
+        if index != last {
+            let moved: Address = Self::load(env, &DataKey::PuzzleHolder(puzzle_id, last));
+            Self::store(env, &DataKey::PuzzleHolder(puzzle_id, index), &moved);
+            Self::store(env, &DataKey::PuzzleHolderIndex(puzzle_id, moved), &index);
+        }
+        env.storage().persistent().remove(&DataKey::PuzzleHolder(puzzle_id, last));
+        env.storage().persistent().remove(&index_key);
+        Self::store(env, &count_key, &last);
+    }
 }
 
 mod test;
@@ -1,7 +1,10 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    testutils::{Address as _, MockAuth, MockAuthInvoke},
+    Address, Env, IntoVal, String,
+};
 
 #[test]
 fn test_nft_lifecycle() {
@@ -47,6 +50,7 @@ fn test_nft_lifecycle() {
 
     // Burn from current owner (user_b)
     client.burn(&token_id);
+    assert_eq!(env.auths()[0].0, user_b);
 
     assert_eq!(client.total_supply(), 0u32);
     assert!(client.get_achievement(&token_id).is_none());
@@ -124,4 +128,347 @@ fn test_transfer_to_self() {
     let token_id = client.mint(&user, &puzzle_id, &metadata);
 
     client.transfer(&user, &user, &token_id); // should panic
-}
\ No newline at end of file
+}
+
+fn setup_minted(env: &Env) -> (AchievementNFTClient<'_>, Address, u32) {
+    let contract_id = env.register_contract(None, AchievementNFT);
+    let client = AchievementNFTClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let owner = Address::generate(env);
+
+    client.initialize(&admin);
+    client.mark_puzzle_completed(&owner, &1u32);
+    let token_id = client.mint(&owner, &1u32, &String::from_str(env, "ipfs://token-one"));
+
+    (client, owner, token_id)
+}
+
+#[test]
+#[should_panic]
+fn test_burn_requires_owner() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _owner, token_id) = setup_minted(&env);
+    let stranger = Address::generate(&env);
+
+    // Only the stranger signs; the owner never authorizes the burn
+    env.mock_auths(&[MockAuth {
+        address: &stranger,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "burn",
+            args: (token_id,).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.burn(&token_id);
+}
+
+#[test]
+fn test_approved_transfer_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, owner, token_id) = setup_minted(&env);
+    let marketplace = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    client.approve(&owner, &Some(marketplace.clone()), &token_id);
+    assert_eq!(client.get_approved(&token_id), Some(marketplace.clone()));
+
+    client.transfer_from(&marketplace, &owner, &buyer, &token_id);
+    assert_eq!(client.owner_of(&token_id), buyer);
+
+    // The approval is cleared once the token changes hands
+    assert_eq!(client.get_approved(&token_id), None);
+}
+
+#[test]
+fn test_operator_transfer_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, owner, token_id) = setup_minted(&env);
+    let operator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    client.set_approval_for_all(&owner, &operator, &true);
+    assert!(client.is_approved_for_all(&owner, &operator));
+
+    client.transfer_from(&operator, &owner, &buyer, &token_id);
+    assert_eq!(client.owner_of(&token_id), buyer);
+    assert_eq!(client.get_collection(&buyer).len(), 1);
+
+    client.set_approval_for_all(&owner, &operator, &false);
+    assert!(!client.is_approved_for_all(&owner, &operator));
+}
+
+#[test]
+#[should_panic(expected = "Not approved")]
+fn test_transfer_from_without_approval() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, owner, token_id) = setup_minted(&env);
+    let stranger = Address::generate(&env);
+
+    client.transfer_from(&stranger, &owner, &stranger, &token_id);
+}
+
+#[test]
+fn test_collection_metadata_and_token_uri() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _owner, token_id) = setup_minted(&env);
+
+    // Without a base URI the token's own metadata is its URI
+    assert_eq!(
+        client.token_uri(&token_id),
+        String::from_str(&env, "ipfs://token-one")
+    );
+    assert_eq!(client.symbol(), String::from_str(&env, "ACHV"));
+
+    client.set_metadata(
+        &String::from_str(&env, "Puzzle Masters"),
+        &String::from_str(&env, "PZM"),
+        &String::from_str(&env, "https://nft.example/achievements/"),
+    );
+    assert_eq!(client.name(), String::from_str(&env, "Puzzle Masters"));
+    assert_eq!(client.symbol(), String::from_str(&env, "PZM"));
+    assert_eq!(
+        client.token_uri(&token_id),
+        String::from_str(&env, "https://nft.example/achievements/1")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Base URI too long")]
+fn test_set_metadata_rejects_long_base_uri() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _owner, _token_id) = setup_minted(&env);
+
+    client.set_metadata(
+        &String::from_str(&env, "Puzzle Masters"),
+        &String::from_str(&env, "PZM"),
+        &String::from_bytes(&env, &[b'a'; 247]),
+    );
+}
+
+#[test]
+fn test_soulbound_recovery_and_burn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, AchievementNFT);
+    let client = AchievementNFTClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let new_wallet = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.set_puzzle_soulbound(&7u32, &true);
+    client.mark_puzzle_completed(&owner, &7u32);
+    client.mark_puzzle_completed(&owner, &8u32);
+
+    let bound = client.mint(&owner, &7u32, &String::from_str(&env, "Proof of Solve"));
+    let free = client.mint(&owner, &8u32, &String::from_str(&env, "Tradable"));
+    assert!(client.is_soulbound(&bound));
+    assert!(!client.is_soulbound(&free));
+
+    client.recover_soulbound(&owner, &new_wallet, &bound);
+    assert_eq!(client.owner_of(&bound), new_wallet);
+    assert!(client.is_soulbound(&bound));
+
+    client.burn(&bound);
+    assert!(client.get_achievement(&bound).is_none());
+    assert!(!client.is_soulbound(&bound));
+}
+
+#[test]
+#[should_panic(expected = "Token is soulbound")]
+fn test_soulbound_transfer_from_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, owner, token_id) = setup_minted(&env);
+    let operator = Address::generate(&env);
+
+    client.set_token_soulbound(&token_id, &true);
+    client.set_approval_for_all(&owner, &operator, &true);
+    client.transfer_from(&operator, &owner, &operator, &token_id);
+}
+
+mod verifier_integration {
+    use super::*;
+    use puzzle_verification::{PuzzleVerification, PuzzleVerificationClient};
+    use soroban_sdk::{testutils::Ledger as _, Bytes, BytesN};
+
+    fn setup(
+        env: &Env,
+    ) -> (
+        AchievementNFTClient<'_>,
+        PuzzleVerificationClient<'_>,
+        Bytes,
+    ) {
+        let verifier_id = env.register_contract(None, PuzzleVerification);
+        let verifier = PuzzleVerificationClient::new(env, &verifier_id);
+        let nft_id = env.register_contract(None, AchievementNFT);
+        let nft = AchievementNFTClient::new(env, &nft_id);
+
+        let admin = Address::generate(env);
+        verifier.initialize(&admin);
+        nft.initialize(&admin);
+        nft.set_verifier(&verifier_id);
+
+        env.ledger().set_timestamp(1_000);
+        let preimage = Bytes::from_array(env, &[3u8; 4]);
+        let hash: BytesN<32> = env.crypto().sha256(&preimage).into();
+        verifier.set_puzzle(&1, &hash, &900, &2_000, &1, &10);
+
+        (nft, verifier, preimage)
+    }
+
+    #[test]
+    fn test_mint_after_verified_solve_assigns_editions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (nft, verifier, preimage) = setup(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+
+        assert!(verifier.verify_solution(&alice, &1, &preimage));
+        assert!(verifier.verify_solution(&bob, &1, &preimage));
+
+        let first = nft.mint(&alice, &1u32, &String::from_str(&env, "Solver"));
+        let second = nft.mint(&bob, &1u32, &String::from_str(&env, "Solver"));
+        assert_eq!(nft.edition_of(&first), 1);
+        assert_eq!(nft.edition_of(&second), 2);
+        assert_eq!(nft.editions_minted(&1u32), 2);
+        assert!(nft.has_minted(&alice, &1u32));
+    }
+
+    #[test]
+    #[should_panic(expected = "Already minted")]
+    fn test_second_mint_for_same_puzzle_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (nft, verifier, preimage) = setup(&env);
+        let alice = Address::generate(&env);
+
+        verifier.verify_solution(&alice, &1, &preimage);
+        nft.mint(&alice, &1u32, &String::from_str(&env, "Solver"));
+        nft.mint(&alice, &1u32, &String::from_str(&env, "Solver"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Puzzle not completed")]
+    fn test_unsolved_puzzle_cannot_mint() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (nft, _verifier, _preimage) = setup(&env);
+        let alice = Address::generate(&env);
+
+        nft.mint(&alice, &1u32, &String::from_str(&env, "Solver"));
+    }
+}
+
+#[test]
+fn test_allowed_crafter_can_craftmint() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, AchievementNFT);
+    let client = AchievementNFTClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let crafter = Address::generate(&env);
+    let alice = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.set_crafter(&crafter, &true);
+    assert!(client.is_crafter(&crafter));
+
+    let token_id = client.craftmint(&crafter, &alice, &42u32, &String::from_str(&env, "Crafted"));
+    assert_eq!(env.auths()[0].0, crafter);
+    assert_eq!(client.owner_of(&token_id), alice);
+}
+
+#[test]
+#[should_panic(expected = "Not authorized to craft")]
+fn test_unauthorized_craftmint() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, AchievementNFT);
+    let client = AchievementNFTClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let crafter = Address::generate(&env);
+    let mallory = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.set_crafter(&crafter, &true);
+    client.set_crafter(&crafter, &false);
+
+    client.craftmint(&crafter, &mallory, &42u32, &String::from_str(&env, "Free"));
+}
+
+#[test]
+fn test_puzzle_indexes_follow_transfer_and_burn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, AchievementNFT);
+    let client = AchievementNFTClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    client.initialize(&admin);
+    let metadata = String::from_str(&env, "Puzzle 42");
+    let t1 = client.craftmint(&admin, &alice, &42u32, &metadata);
+    let t2 = client.craftmint(&admin, &bob, &42u32, &metadata);
+    let t3 = client.craftmint(&admin, &alice, &42u32, &metadata);
+    let other = client.craftmint(&admin, &alice, &7u32, &metadata);
+
+    assert_eq!(client.puzzle_supply(&42u32), 3);
+    assert_eq!(client.tokens_of_puzzle(&42u32, &0, &2).len(), 2);
+    assert_eq!(client.tokens_of_puzzle(&42u32, &2, &2).get(0).unwrap(), t3);
+    assert_eq!(client.tokens_of_owner(&alice, &1, &10).len(), 2);
+    assert_eq!(
+        client.tokens_of_owner(&alice, &2, &10).get(0).unwrap(),
+        other
+    );
+
+    let holders = client.holders_of_puzzle(&42u32, &0, &10);
+    assert_eq!(holders.len(), 2);
+    assert_eq!(client.holder_balance(&42u32, &alice), 2);
+
+    // Alice still holds t3 after giving t1 away
+    client.transfer(&alice, &carol, &t1);
+    assert_eq!(client.holder_balance(&42u32, &alice), 1);
+    assert_eq!(client.holders_of_puzzle(&42u32, &0, &10).len(), 3);
+
+    // Bob's only token is burned, so he stops being a holder
+    client.burn(&t2);
+    assert_eq!(client.puzzle_supply(&42u32), 2);
+    let holders = client.holders_of_puzzle(&42u32, &0, &10);
+    assert_eq!(holders.len(), 2);
+    assert!(!holders.contains(&bob));
+    assert_eq!(
+        client.tokens_of_puzzle(&42u32, &0, &10).first_index_of(t2),
+        None
+    );
+    // The last token took the burned token's slot
+    assert_eq!(client.tokens_of_puzzle(&42u32, &1, &1).get(0).unwrap(), t3);
+}
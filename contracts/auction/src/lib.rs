@@ -1,6 +1,7 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, Env, IntoVal, Symbol, Val,
+    contract, contractimpl, contracttype, symbol_short, token, xdr::ToXdr, Address, BytesN, Env,
+    IntoVal, Map, Symbol, Val, Vec,
 };
 
 // 1. DATA STRUCTURES
@@ -9,6 +10,20 @@ use soroban_sdk::{
 pub enum AuctionType {
     English = 1,
     Dutch = 2,
+    SealedBid = 3, // Commit-reveal, second-price (Vickrey)
+}
+
+// Where an auction is in its lifecycle; derived from the ledger time and refreshed on read
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuctionPhase {
+    Pending = 0, // before start_time
+    Bidding = 1, // English/Dutch: open for bids
+    Commit = 2,  // Sealed: accepting bid commitments until end_time
+    Reveal = 3,  // Sealed: revealing bids until reveal_end_time
+    Ended = 4,   // awaiting settlement
+    Settled = 5,
+    Cancelled = 6,
 }
 
 // NEW: Grouping settings to avoid the 10-parameter limit
@@ -21,6 +36,13 @@ pub struct AuctionSettings {
     pub reserve_price: i128,
     pub buy_now_price: i128,
     pub min_bid_increment: i128,
+    // Anti-sniping: a bid with less than `extension_window` seconds left pushes the
+    // end out to `extension_amount` seconds after the bid. A window of 0 disables it.
+    pub extension_window: u64,
+    pub extension_amount: u64,
+    pub max_extensions: Option<u32>, // None = unlimited
+    // Sealed-bid only: bids are committed until end_time and revealed until reveal_end_time
+    pub reveal_end_time: u64,
 }
 
 #[contracttype]
@@ -36,13 +58,60 @@ pub struct AuctionInfo {
     pub settings: AuctionSettings,
     pub highest_bidder: Option<Address>,
     pub current_bid: i128,
+    pub second_bid: i128, // Sealed-bid: second-highest revealed bid
+    pub phase: AuctionPhase,
     pub settled: bool,
+    pub cancelled: bool, // Withdrawn by the seller before any bid
+    pub extensions: u32, // Times the end time has been pushed out
+    pub escrowed: bool,  // NFT held by the contract; false for auctions created before escrow
+}
+
+// Layout of auctions created before anti-sniping settings, escrow and sealed bids.
+// They are converted when loaded and saved back in the current layout.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyAuctionSettings {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub starting_price: i128,
+    pub reserve_price: i128,
+    pub buy_now_price: i128,
+    pub min_bid_increment: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyAuctionInfo {
+    pub auction_id: u64,
+    pub seller: Address,
+    pub nft_contract: Address,
+    pub nft_id: u64,
+    pub payment_token: Address,
+    pub auction_type: AuctionType,
+    pub settings: LegacyAuctionSettings,
+    pub highest_bidder: Option<Address>,
+    pub current_bid: i128,
+    pub settled: bool,
+}
+
+// A sealed bid: the commitment is sha256(bidder xdr || auction_id || amount || salt)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SealedBid {
+    pub commitment: BytesN<32>,
+    pub deposit: i128,
+    pub revealed: bool,
+    pub amount: i128, // 0 until revealed
+    pub withdrawn: bool,
 }
 
 #[contracttype]
 pub enum DataKey {
     Auction(u64),
     AuctionCount,
+    SealedBid(u64, Address), // SealedBid
+    BidCount(u64),           // u32 sealed bids committed
+    Bidder(u64, u32),        // Address of the n-th sealed bidder in commit order
 }
 
 // 2. CONTRACT LOGIC
@@ -73,6 +142,12 @@ impl AuctionContract {
         if settings.end_time <= settings.start_time {
             panic!("End time must be after start time");
         }
+        if settings.extension_window > 0 && settings.extension_amount == 0 {
+            panic!("Extension amount must be positive");
+        }
+        if auction_type == AuctionType::SealedBid && settings.reveal_end_time <= settings.end_time {
+            panic!("Reveal end must be after end time");
+        }
 
         // Generate ID
         let mut id: u64 = env
@@ -94,9 +169,23 @@ impl AuctionContract {
             settings, // Save the grouped settings
             highest_bidder: None,
             current_bid: 0,
+            second_bid: 0,
+            phase: AuctionPhase::Pending,
             settled: false,
+            cancelled: false,
+            extensions: 0,
+            escrowed: true,
         };
 
+        // Escrow: take custody of the NFT for the lifetime of the auction so the
+        // seller can't move it away once bidders have funds locked
+        Self::transfer_nft(
+            &env,
+            &auction,
+            &auction.seller,
+            &env.current_contract_address(),
+        );
+
         // Save
         env.storage()
             .instance()
@@ -112,11 +201,7 @@ impl AuctionContract {
 
         // 2. Load the auction
         // We assume the auction exists (unwrap panics if it doesn't)
-        let mut auction: AuctionInfo = env
-            .storage()
-            .instance()
-            .get(&DataKey::Auction(auction_id))
-            .unwrap();
+        let mut auction: AuctionInfo = Self::load_auction(&env, auction_id).unwrap();
 
         // 3. Validation Checks
         if auction.auction_type != AuctionType::English {
@@ -137,7 +222,7 @@ impl AuctionContract {
         // 4. Price Logic & Refunds
         let token_client = token::Client::new(&env, &auction.payment_token);
 
-        if let Some(previous_bidder) = auction.highest_bidder {
+        if let Some(previous_bidder) = auction.highest_bidder.clone() {
             // CASE A: Outbidding someone
             // Check if bid is high enough (Current Bid + Increment)
             if bid_amount < auction.current_bid + auction.settings.min_bid_increment {
@@ -163,11 +248,7 @@ impl AuctionContract {
         token_client.transfer(&bidder, &env.current_contract_address(), &bid_amount);
 
         // 6. Anti-Sniping (Extension)
-        // If bid is placed in the last 5 minutes (300 seconds), extend end time by 5 mins.
-        let time_remaining = auction.settings.end_time - current_time;
-        if time_remaining < 300 {
-            auction.settings.end_time = current_time + 300;
-        }
+        Self::maybe_extend(&env, &mut auction, current_time);
 
         // 7. Update State & Save
         auction.highest_bidder = Some(bidder);
@@ -178,6 +259,33 @@ impl AuctionContract {
             .set(&DataKey::Auction(auction_id), &auction);
     }
 
+    /// Helper: Push the end time out when a bid lands inside the extension window
+    fn maybe_extend(env: &Env, auction: &mut AuctionInfo, current_time: u64) {
+        let settings = &auction.settings;
+        if settings.extension_window == 0 {
+            return;
+        }
+        if settings.end_time - current_time >= settings.extension_window {
+            return;
+        }
+        if let Some(max) = settings.max_extensions {
+            if auction.extensions >= max {
+                return;
+            }
+        }
+        let new_end = current_time + settings.extension_amount;
+        if new_end <= settings.end_time {
+            return;
+        }
+
+        auction.settings.end_time = new_end;
+        auction.extensions += 1;
+        env.events().publish(
+            (symbol_short!("extended"), auction.auction_id),
+            (new_end, auction.extensions),
+        );
+    }
+
     /// Helper: Calculate the current price for a Dutch Auction
     /// Formula: StartPrice - (DecayPerSecond * SecondsElapsed)
     fn calculate_dutch_price(settings: &AuctionSettings, current_time: u64) -> i128 {
@@ -205,11 +313,7 @@ impl AuctionContract {
     pub fn buy_dutch(env: Env, buyer: Address, auction_id: u64, max_amount: i128) {
         buyer.require_auth();
 
-        let mut auction: AuctionInfo = env
-            .storage()
-            .instance()
-            .get(&DataKey::Auction(auction_id))
-            .unwrap();
+        let mut auction: AuctionInfo = Self::load_auction(&env, auction_id).unwrap();
 
         // 1. Validation
         if auction.auction_type != AuctionType::Dutch {
@@ -230,14 +334,16 @@ impl AuctionContract {
         }
 
         // 4. Process Payment
-        // Buyer pays the calculated CURRENT price (not their max)
+        // Buyer pays the calculated CURRENT price (not their max) straight to the seller
         let token_client = token::Client::new(&env, &auction.payment_token);
-        token_client.transfer(&buyer, &env.current_contract_address(), &current_price);
+        token_client.transfer(&buyer, &auction.seller, &current_price);
 
-        // 5. End the Auction Immediately
+        // 5. Release the escrowed NFT and end the Auction Immediately
+        Self::release_nft(&env, &auction, &buyer);
         auction.highest_bidder = Some(buyer);
         auction.current_bid = current_price;
         auction.settled = true; // Dutch auctions end instantly
+        auction.phase = AuctionPhase::Settled;
 
         env.storage()
             .instance()
@@ -246,55 +352,422 @@ impl AuctionContract {
 
     /// Finalize the auction (Send money to seller, NFT to winner)
     pub fn settle_auction(env: Env, auction_id: u64) {
-        let mut auction: AuctionInfo = env
-            .storage()
-            .instance()
-            .get(&DataKey::Auction(auction_id))
-            .unwrap();
+        let mut auction: AuctionInfo = Self::load_auction(&env, auction_id).unwrap();
 
         if auction.settled {
             panic!("Auction is already settled");
         }
 
-        // For English auctions, ensure time has passed
-        if auction.auction_type == AuctionType::English {
-            if env.ledger().timestamp() < auction.settings.end_time {
-                panic!("Auction is still ongoing");
+        // Ensure the (possibly extended) end has passed.
+        // Bids are accepted up to and including end_time.
+        if env.ledger().timestamp() <= auction.settings.end_time {
+            panic!("Auction is still ongoing");
+        }
+
+        if auction.auction_type == AuctionType::SealedBid {
+            Self::settle_sealed(&env, &mut auction);
+            return;
+        }
+
+        let token_client = token::Client::new(&env, &auction.payment_token);
+        match auction.highest_bidder.clone() {
+            // If there is a winner above the reserve...
+            Some(winner) if auction.current_bid >= auction.settings.reserve_price => {
+                // 1. Pay the Seller
+                token_client.transfer(
+                    &env.current_contract_address(),
+                    &auction.seller,
+                    &auction.current_bid,
+                );
+
+                // 2. Release the escrowed NFT to the winner
+                Self::release_nft(&env, &auction, &winner);
             }
+            // Reserve not met: refund the top bidder and return the NFT
+            Some(bidder) => {
+                token_client.transfer(
+                    &env.current_contract_address(),
+                    &bidder,
+                    &auction.current_bid,
+                );
+                Self::release_nft(&env, &auction, &auction.seller);
+            }
+            // No bids (or an unsold Dutch auction): return the NFT
+            None => Self::release_nft(&env, &auction, &auction.seller),
         }
 
-        // If there is a winner...
-        if let Some(winner) = auction.highest_bidder.clone() {
-            // 1. Pay the Seller
+        // Mark as settled so it can't be processed again
+        auction.settled = true;
+        auction.phase = AuctionPhase::Settled;
+        env.storage()
+            .instance()
+            .set(&DataKey::Auction(auction_id), &auction);
+    }
+
+    /// Sealed-bid: commit to a bid, escrowing a deposit of at least the bid amount.
+    /// The deposit is all that's visible on-chain until the reveal phase.
+    pub fn commit_bid(
+        env: Env,
+        bidder: Address,
+        auction_id: u64,
+        commitment: BytesN<32>,
+        deposit: i128,
+    ) {
+        bidder.require_auth();
+
+        let auction: AuctionInfo = Self::load_auction(&env, auction_id).unwrap();
+        if auction.auction_type != AuctionType::SealedBid {
+            panic!("This function is for sealed-bid auctions only");
+        }
+        if Self::current_phase(&env, &auction) != AuctionPhase::Commit {
+            panic!("Auction is not in the commit phase");
+        }
+        if deposit <= 0 {
+            panic!("Deposit must be positive");
+        }
+
+        let key = DataKey::SealedBid(auction_id, bidder.clone());
+        if env.storage().persistent().has(&key) {
+            panic!("Bid already committed");
+        }
+
+        let token_client = token::Client::new(&env, &auction.payment_token);
+        token_client.transfer(&bidder, &env.current_contract_address(), &deposit);
+
+        let bid = SealedBid {
+            commitment,
+            deposit,
+            revealed: false,
+            amount: 0,
+            withdrawn: false,
+        };
+        env.storage().persistent().set(&key, &bid);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, 100_000, 500_000);
+
+        // One entry per bidder, so a commit never rewrites the whole bidder list
+        let count = Self::get_bid_count(env.clone(), auction_id);
+        let bidder_key = DataKey::Bidder(auction_id, count);
+        env.storage().persistent().set(&bidder_key, &bidder);
+        env.storage()
+            .persistent()
+            .extend_ttl(&bidder_key, 100_000, 500_000);
+        let count_key = DataKey::BidCount(auction_id);
+        env.storage().persistent().set(&count_key, &(count + 1));
+        env.storage()
+            .persistent()
+            .extend_ttl(&count_key, 100_000, 500_000);
+    }
+
+    /// Sealed-bid: open a commitment during the reveal phase
+    pub fn reveal_bid(env: Env, bidder: Address, auction_id: u64, amount: i128, salt: BytesN<32>) {
+        bidder.require_auth();
+
+        let mut auction: AuctionInfo = Self::load_auction(&env, auction_id).unwrap();
+        if auction.auction_type != AuctionType::SealedBid {
+            panic!("This function is for sealed-bid auctions only");
+        }
+        if Self::current_phase(&env, &auction) != AuctionPhase::Reveal {
+            panic!("Auction is not in the reveal phase");
+        }
+
+        let key = DataKey::SealedBid(auction_id, bidder.clone());
+        let mut bid: SealedBid = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("No committed bid");
+        if bid.revealed {
+            panic!("Bid already revealed");
+        }
+        if Self::compute_commitment(env.clone(), bidder.clone(), auction_id, amount, salt)
+            != bid.commitment
+        {
+            panic!("Commitment mismatch");
+        }
+        if amount > bid.deposit {
+            panic!("Deposit does not cover bid");
+        }
+        if amount < auction.settings.starting_price {
+            panic!("Bid below starting price");
+        }
+
+        bid.revealed = true;
+        bid.amount = amount;
+        env.storage().persistent().set(&key, &bid);
+
+        // Track the top two revealed bids; ties go to the earlier reveal
+        if auction.highest_bidder.is_none() || amount > auction.current_bid {
+            auction.second_bid = auction.current_bid;
+            auction.current_bid = amount;
+            auction.highest_bidder = Some(bidder);
+        } else if amount > auction.second_bid {
+            auction.second_bid = amount;
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Auction(auction_id), &auction);
+    }
+
+    /// Sealed-bid: pull back a deposit once reveals are over. Losing and unrevealed
+    /// bids get the full deposit; the winner gets the change after settlement.
+    pub fn withdraw(env: Env, bidder: Address, auction_id: u64) -> i128 {
+        bidder.require_auth();
+
+        let auction: AuctionInfo = Self::load_auction(&env, auction_id).unwrap();
+        let key = DataKey::SealedBid(auction_id, bidder.clone());
+        let mut bid: SealedBid = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("No committed bid");
+        if bid.withdrawn {
+            panic!("Deposit already withdrawn");
+        }
+
+        let is_winner = auction.highest_bidder == Some(bidder.clone());
+        if env.ledger().timestamp() <= auction.settings.reveal_end_time {
+            panic!("Reveal phase has not ended");
+        }
+        if is_winner && !auction.settled {
+            panic!("Winner must wait for settlement");
+        }
+        let amount = if is_winner && auction.current_bid >= auction.settings.reserve_price {
+            bid.deposit - Self::sealed_price(&auction)
+        } else {
+            bid.deposit
+        };
+
+        bid.withdrawn = true;
+        env.storage().persistent().set(&key, &bid);
+
+        if amount > 0 {
             let token_client = token::Client::new(&env, &auction.payment_token);
-            token_client.transfer(
-                &env.current_contract_address(),
-                &auction.seller,
-                &auction.current_bid,
-            );
+            token_client.transfer(&env.current_contract_address(), &bidder, &amount);
+        }
+        amount
+    }
 
-            // 2. Transfer the NFT
-            // We invoke the NFT contract's "transfer" function dynamically.
-            // Args: (from, to, token_id)
-            let transfer_args = (auction.seller.clone(), winner, auction.nft_id);
+    /// Sealed-bid: the commitment a bidder submits for `amount` and `salt`
+    pub fn compute_commitment(
+        env: Env,
+        bidder: Address,
+        auction_id: u64,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> BytesN<32> {
+        let mut data = bidder.to_xdr(&env);
+        data.extend_from_array(&auction_id.to_be_bytes());
+        data.extend_from_array(&amount.to_be_bytes());
+        data.extend_from_array(&salt.to_array());
+        env.crypto().sha256(&data).into()
+    }
 
-            env.invoke_contract::<()>(
-                &auction.nft_contract,
-                &Symbol::new(&env, "transfer"),
-                transfer_args.into_val(&env),
-            );
+    pub fn get_sealed_bid(env: Env, auction_id: u64, bidder: Address) -> Option<SealedBid> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SealedBid(auction_id, bidder))
+    }
+
+    /// Sealed-bid: number of committed bids
+    pub fn get_bid_count(env: Env, auction_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BidCount(auction_id))
+            .unwrap_or(0)
+    }
+
+    /// Sealed-bid: a page of bidders in commit order
+    pub fn get_bidders(env: Env, auction_id: u64, offset: u32, limit: u32) -> Vec<Address> {
+        let end = offset
+            .saturating_add(limit)
+            .min(Self::get_bid_count(env.clone(), auction_id));
+        let mut bidders = Vec::new(&env);
+        for index in offset..end {
+            let bidder: Address = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Bidder(auction_id, index))
+                .unwrap();
+            bidders.push_back(bidder);
         }
+        bidders
+    }
 
-        // Mark as settled so it can't be processed again
+    pub fn get_phase(env: Env, auction_id: u64) -> AuctionPhase {
+        let auction: AuctionInfo = Self::load_auction(&env, auction_id).unwrap();
+        Self::current_phase(&env, &auction)
+    }
+
+    /// Sealed-bid: what the winner pays (the second-highest bid, floored at the reserve)
+    pub fn get_clearing_price(env: Env, auction_id: u64) -> i128 {
+        let auction: AuctionInfo = Self::load_auction(&env, auction_id).unwrap();
+        Self::sealed_price(&auction)
+    }
+
+    fn sealed_price(auction: &AuctionInfo) -> i128 {
+        if auction.second_bid > auction.settings.reserve_price {
+            auction.second_bid
+        } else {
+            auction.settings.reserve_price
+        }
+    }
+
+    /// Helper: Sealed-bid settlement once reveals are over. The winner pays the
+    /// clearing price out of their deposit; everything else is left for `withdraw`.
+    fn settle_sealed(env: &Env, auction: &mut AuctionInfo) {
+        if env.ledger().timestamp() <= auction.settings.reveal_end_time {
+            panic!("Reveal phase is still ongoing");
+        }
+
+        match auction.highest_bidder.clone() {
+            Some(winner) if auction.current_bid >= auction.settings.reserve_price => {
+                let token_client = token::Client::new(env, &auction.payment_token);
+                token_client.transfer(
+                    &env.current_contract_address(),
+                    &auction.seller,
+                    &Self::sealed_price(auction),
+                );
+                Self::release_nft(env, auction, &winner);
+            }
+            // Reserve not met or nothing revealed: return the NFT
+            _ => Self::release_nft(env, auction, &auction.seller),
+        }
+
+        auction.settled = true;
+        auction.phase = AuctionPhase::Settled;
+        env.storage()
+            .instance()
+            .set(&DataKey::Auction(auction.auction_id), auction);
+    }
+
+    fn current_phase(env: &Env, auction: &AuctionInfo) -> AuctionPhase {
+        if auction.cancelled {
+            return AuctionPhase::Cancelled;
+        }
+        if auction.settled {
+            return AuctionPhase::Settled;
+        }
+        let now = env.ledger().timestamp();
+        let sealed = auction.auction_type == AuctionType::SealedBid;
+        if now < auction.settings.start_time {
+            AuctionPhase::Pending
+        } else if now <= auction.settings.end_time {
+            if sealed {
+                AuctionPhase::Commit
+            } else {
+                AuctionPhase::Bidding
+            }
+        } else if sealed && now <= auction.settings.reveal_end_time {
+            AuctionPhase::Reveal
+        } else {
+            AuctionPhase::Ended
+        }
+    }
+
+    /// Withdraw an auction that hasn't received any bids; the NFT goes back to the seller
+    pub fn cancel_auction(env: Env, seller: Address, auction_id: u64) {
+        seller.require_auth();
+
+        let mut auction: AuctionInfo = Self::load_auction(&env, auction_id).unwrap();
+
+        if auction.seller != seller {
+            panic!("Only the seller can cancel");
+        }
+        if auction.settled {
+            panic!("Auction is already settled");
+        }
+        if auction.highest_bidder.is_some() || Self::get_bid_count(env.clone(), auction_id) > 0 {
+            panic!("Auction has bids");
+        }
+
+        Self::release_nft(&env, &auction, &seller);
+
+        auction.cancelled = true;
         auction.settled = true;
+        auction.phase = AuctionPhase::Cancelled;
         env.storage()
             .instance()
             .set(&DataKey::Auction(auction_id), &auction);
     }
 
+    /// Helper: Send the escrowed NFT out of the contract
+    fn release_nft(env: &Env, auction: &AuctionInfo, to: &Address) {
+        // Auctions created before escrow left the NFT with the seller, who hands it
+        // straight to the winner as settlement always did; that transfer still needs
+        // the seller's authorization
+        let from = if auction.escrowed {
+            env.current_contract_address()
+        } else if *to == auction.seller {
+            return;
+        } else {
+            auction.seller.clone()
+        };
+        Self::transfer_nft(env, auction, &from, to);
+    }
+
+    /// Helper: Move the auctioned NFT
+    /// Args to the NFT contract's "transfer": (from, to, token_id: u32)
+    fn transfer_nft(env: &Env, auction: &AuctionInfo, from: &Address, to: &Address) {
+        let token_id = u32::try_from(auction.nft_id).expect("NFT id out of range");
+        env.invoke_contract::<()>(
+            &auction.nft_contract,
+            &Symbol::new(env, "transfer"),
+            (from.clone(), to.clone(), token_id).into_val(env),
+        );
+    }
+
+    /// Helper: Load an auction, converting one stored in the legacy layout
+    fn load_auction(env: &Env, auction_id: u64) -> Option<AuctionInfo> {
+        let key = DataKey::Auction(auction_id);
+        let fields: Map<Symbol, Val> = env.storage().instance().get(&key)?;
+        if fields.contains_key(Symbol::new(env, "escrowed")) {
+            return env.storage().instance().get(&key);
+        }
+
+        let legacy: LegacyAuctionInfo = env.storage().instance().get(&key)?;
+        let settings = AuctionSettings {
+            start_time: legacy.settings.start_time,
+            end_time: legacy.settings.end_time,
+            starting_price: legacy.settings.starting_price,
+            reserve_price: legacy.settings.reserve_price,
+            buy_now_price: legacy.settings.buy_now_price,
+            min_bid_increment: legacy.settings.min_bid_increment,
+            // Legacy auctions always extended a bid in the last 5 minutes by 5 minutes
+            extension_window: 300,
+            extension_amount: 300,
+            max_extensions: None,
+            reveal_end_time: 0,
+        };
+        Some(AuctionInfo {
+            auction_id: legacy.auction_id,
+            seller: legacy.seller,
+            nft_contract: legacy.nft_contract,
+            nft_id: legacy.nft_id,
+            payment_token: legacy.payment_token,
+            auction_type: legacy.auction_type,
+            settings,
+            highest_bidder: legacy.highest_bidder,
+            current_bid: legacy.current_bid,
+            second_bid: 0,
+            phase: if legacy.settled {
+                AuctionPhase::Settled
+            } else {
+                AuctionPhase::Pending
+            },
+            settled: legacy.settled,
+            cancelled: false,
+            extensions: 0,
+            escrowed: false,
+        })
+    }
+
     /// Helper to fetch auction data
     pub fn get_auction(env: Env, auction_id: u64) -> Option<AuctionInfo> {
-        env.storage().instance().get(&DataKey::Auction(auction_id))
+        let mut auction = Self::load_auction(&env, auction_id)?;
+        auction.phase = Self::current_phase(&env, &auction);
+        Some(auction)
     }
 }
 
@@ -1,11 +1,42 @@
 #![cfg(test)]
 
 use super::*;
+use achievement_nft::{AchievementNFT, AchievementNFTClient};
 use soroban_sdk::{
-    testutils::{Address as _, Ledger},
-    token, Address, Env,
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token, Address, BytesN, Env, IntoVal, String, TryFromVal,
 };
 
+// Minimal NFT exposing the same `transfer(from, to, token_id)` as AchievementNFT
+#[contract]
+pub struct MockNft;
+
+#[contractimpl]
+impl MockNft {
+    pub fn mint(env: Env, to: Address, token_id: u32) {
+        env.storage().instance().set(&token_id, &to);
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u32) {
+        from.require_auth();
+        if Self::owner_of(env.clone(), token_id) != from {
+            panic!("not owner");
+        }
+        env.storage().instance().set(&token_id, &to);
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Address {
+        env.storage().instance().get(&token_id).expect("no token")
+    }
+}
+
+fn mock_nft(env: &Env, owner: &Address, token_id: u32) -> MockNftClient<'static> {
+    let nft = MockNftClient::new(env, &env.register_contract(None, MockNft));
+    nft.mint(owner, &token_id);
+    nft
+}
+
 #[test]
 fn test_bidding_and_refunds() {
     let env = Env::default();
@@ -44,12 +75,17 @@ fn test_bidding_and_refunds() {
         reserve_price: 150,
         buy_now_price: 500,
         min_bid_increment: 10,
+        extension_window: 300,
+        extension_amount: 300,
+        max_extensions: None,
+        reveal_end_time: 0,
     };
 
     // create_auction args...
+    let nft = mock_nft(&env, &seller, 1);
     let auction_id = client.create_auction(
         &seller,
-        &Address::generate(&env), // Random NFT
+        &nft.address,
         &1u64,
         &token_contract_id, // Use our fake token
         &AuctionType::English,
@@ -124,11 +160,16 @@ fn test_dutch_auction_decay() {
         reserve_price: 100,
         buy_now_price: 0, // Not used in Dutch logic usually, or same as start
         min_bid_increment: 0,
+        extension_window: 0,
+        extension_amount: 0,
+        max_extensions: None,
+        reveal_end_time: 0,
     };
 
+    let nft = mock_nft(&env, &seller, 1);
     let auction_id = client.create_auction(
         &seller,
-        &Address::generate(&env),
+        &nft.address,
         &1u64,
         &token_contract_id,
         &AuctionType::Dutch,
@@ -158,4 +199,672 @@ fn test_dutch_auction_decay() {
 
     // Balance check: Buyer started with 1000, paid 300. Remainder: 700.
     assert_eq!(token_client.balance(&buyer), 700);
-}
\ No newline at end of file
+    assert_eq!(token_client.balance(&seller), 300);
+    assert_eq!(nft.owner_of(&1), buyer);
+}
+fn english_settings(max_extensions: Option<u32>) -> AuctionSettings {
+    AuctionSettings {
+        start_time: 1000,
+        end_time: 2000,
+        starting_price: 100,
+        reserve_price: 100,
+        buy_now_price: 0,
+        min_bid_increment: 10,
+        extension_window: 60,
+        extension_amount: 120,
+        max_extensions,
+        reveal_end_time: 0,
+    }
+}
+
+struct EnglishSetup {
+    client: AuctionContractClient<'static>,
+    token: token::Client<'static>,
+    nft: MockNftClient<'static>,
+    seller: Address,
+    bidder1: Address,
+    bidder2: Address,
+    auction_id: u64,
+}
+
+fn setup_english(env: &Env, settings: &AuctionSettings) -> EnglishSetup {
+    let token_admin = Address::generate(env);
+    let token_contract_id = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+    let token_admin_client = token::StellarAssetClient::new(env, &token_contract_id);
+
+    let contract_id = env.register_contract(None, AuctionContract);
+    let client = AuctionContractClient::new(env, &contract_id);
+    client.init(&token_admin);
+
+    let bidder1 = Address::generate(env);
+    let bidder2 = Address::generate(env);
+    token_admin_client.mint(&bidder1, &1000);
+    token_admin_client.mint(&bidder2, &1000);
+
+    let seller = Address::generate(env);
+    let nft = mock_nft(env, &seller, 7);
+    let auction_id = client.create_auction(
+        &seller,
+        &nft.address,
+        &7u64,
+        &token_contract_id,
+        &AuctionType::English,
+        settings,
+    );
+    EnglishSetup {
+        client,
+        token: token::Client::new(env, &token_contract_id),
+        nft,
+        seller,
+        bidder1,
+        bidder2,
+        auction_id,
+    }
+}
+
+#[test]
+fn test_bid_in_window_extends_end() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let EnglishSetup {
+        client,
+        bidder1,
+        bidder2,
+        auction_id,
+        ..
+    } = setup_english(&env, &english_settings(None));
+
+    // Outside the window: no extension
+    env.ledger().set_timestamp(1500);
+    client.place_bid(&bidder1, &auction_id, &100);
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.settings.end_time, 2000);
+    assert_eq!(auction.extensions, 0);
+
+    // 30 seconds left: end moves to bid time + 120
+    env.ledger().set_timestamp(1970);
+    client.place_bid(&bidder2, &auction_id, &110);
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.settings.end_time, 2090);
+    assert_eq!(auction.extensions, 1);
+
+    // Bidding past the original end is still allowed
+    env.ledger().set_timestamp(2050);
+    client.place_bid(&bidder1, &auction_id, &120);
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.settings.end_time, 2170);
+    assert_eq!(auction.extensions, 2);
+}
+
+#[test]
+fn test_extension_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let EnglishSetup {
+        client,
+        bidder1,
+        bidder2,
+        auction_id,
+        ..
+    } = setup_english(&env, &english_settings(Some(1)));
+
+    env.ledger().set_timestamp(1990);
+    client.place_bid(&bidder1, &auction_id, &100);
+    env.ledger().set_timestamp(2100);
+    client.place_bid(&bidder2, &auction_id, &110);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.settings.end_time, 2110);
+    assert_eq!(auction.extensions, 1);
+}
+
+#[test]
+fn test_extension_emits_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let EnglishSetup {
+        client,
+        bidder1,
+        auction_id,
+        ..
+    } = setup_english(&env, &english_settings(None));
+
+    env.ledger().set_timestamp(1990);
+    client.place_bid(&bidder1, &auction_id, &100);
+
+    let extended = env.events().all().iter().any(|(contract, topics, data)| {
+        contract == client.address
+            && topics == (symbol_short!("extended"), auction_id).into_val(&env)
+            && <(u64, u32)>::try_from_val(&env, &data) == Ok((2110, 1))
+    });
+    assert!(extended);
+}
+
+#[test]
+#[should_panic(expected = "Auction is still ongoing")]
+fn test_settle_respects_extended_end() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let EnglishSetup {
+        client,
+        bidder1,
+        auction_id,
+        ..
+    } = setup_english(&env, &english_settings(None));
+
+    env.ledger().set_timestamp(1990);
+    client.place_bid(&bidder1, &auction_id, &100);
+
+    // Past the original end, but before the extended one
+    env.ledger().set_timestamp(2050);
+    client.settle_auction(&auction_id);
+}
+
+#[test]
+fn test_create_escrows_nft() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_english(&env, &english_settings(None));
+
+    assert_eq!(setup.nft.owner_of(&7), setup.client.address);
+}
+
+#[test]
+fn test_settle_pays_seller_and_delivers_nft() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_english(&env, &english_settings(None));
+
+    env.ledger().set_timestamp(1500);
+    setup
+        .client
+        .place_bid(&setup.bidder1, &setup.auction_id, &150);
+    env.ledger().set_timestamp(2001);
+    setup.client.settle_auction(&setup.auction_id);
+
+    assert_eq!(setup.nft.owner_of(&7), setup.bidder1);
+    assert_eq!(setup.token.balance(&setup.seller), 150);
+    assert_eq!(setup.token.balance(&setup.client.address), 0);
+    assert!(setup.client.get_auction(&setup.auction_id).unwrap().settled);
+}
+
+#[test]
+fn test_reserve_not_met_returns_nft_and_refunds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let mut settings = english_settings(None);
+    settings.reserve_price = 500;
+    let setup = setup_english(&env, &settings);
+
+    env.ledger().set_timestamp(1500);
+    setup
+        .client
+        .place_bid(&setup.bidder1, &setup.auction_id, &150);
+    env.ledger().set_timestamp(2001);
+    setup.client.settle_auction(&setup.auction_id);
+
+    assert_eq!(setup.nft.owner_of(&7), setup.seller);
+    assert_eq!(setup.token.balance(&setup.bidder1), 1000);
+    assert_eq!(setup.token.balance(&setup.seller), 0);
+}
+
+#[test]
+fn test_settle_without_bids_returns_nft() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_english(&env, &english_settings(None));
+
+    env.ledger().set_timestamp(2001);
+    setup.client.settle_auction(&setup.auction_id);
+
+    assert_eq!(setup.nft.owner_of(&7), setup.seller);
+}
+
+#[test]
+fn test_cancel_without_bids() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_english(&env, &english_settings(None));
+
+    setup
+        .client
+        .cancel_auction(&setup.seller, &setup.auction_id);
+
+    assert_eq!(setup.nft.owner_of(&7), setup.seller);
+    let auction = setup.client.get_auction(&setup.auction_id).unwrap();
+    assert!(auction.cancelled);
+    assert!(auction.settled);
+}
+
+#[test]
+#[should_panic(expected = "Auction has bids")]
+fn test_cancel_with_bids_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_english(&env, &english_settings(None));
+
+    env.ledger().set_timestamp(1500);
+    setup
+        .client
+        .place_bid(&setup.bidder1, &setup.auction_id, &150);
+    setup
+        .client
+        .cancel_auction(&setup.seller, &setup.auction_id);
+}
+
+#[test]
+#[should_panic(expected = "Only the seller can cancel")]
+fn test_cancel_by_non_seller_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_english(&env, &english_settings(None));
+
+    setup
+        .client
+        .cancel_auction(&setup.bidder1, &setup.auction_id);
+}
+
+#[test]
+#[should_panic(expected = "Auction is already settled")]
+fn test_bid_after_cancel_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_english(&env, &english_settings(None));
+
+    setup
+        .client
+        .cancel_auction(&setup.seller, &setup.auction_id);
+    env.ledger().set_timestamp(1500);
+    setup
+        .client
+        .place_bid(&setup.bidder1, &setup.auction_id, &150);
+}
+
+#[test]
+fn test_legacy_auction_settles_from_seller() {
+    let env = Env::default();
+    // The seller authorizes the NFT transfer made during settlement
+    env.mock_all_auths_allowing_non_root_auth();
+    let token_admin = Address::generate(&env);
+    let token_contract_id = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+    token::StellarAssetClient::new(&env, &token_contract_id).mint(&token_admin, &1000);
+    let client = AuctionContractClient::new(&env, &env.register_contract(None, AuctionContract));
+    client.init(&token_admin);
+
+    // Saved before escrow existed: the NFT never left the seller
+    let seller = Address::generate(&env);
+    let nft = mock_nft(&env, &seller, 7);
+    let legacy = LegacyAuctionInfo {
+        auction_id: 1,
+        seller: seller.clone(),
+        nft_contract: nft.address.clone(),
+        nft_id: 7,
+        payment_token: token_contract_id.clone(),
+        auction_type: AuctionType::English,
+        settings: LegacyAuctionSettings {
+            start_time: 1000,
+            end_time: 2000,
+            starting_price: 100,
+            reserve_price: 0,
+            buy_now_price: 0,
+            min_bid_increment: 10,
+        },
+        highest_bidder: None,
+        current_bid: 0,
+        settled: false,
+    };
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&DataKey::Auction(1), &legacy);
+        env.storage().instance().set(&DataKey::AuctionCount, &1u64);
+    });
+
+    let auction = client.get_auction(&1).unwrap();
+    assert!(!auction.escrowed);
+    assert_eq!(auction.settings.extension_window, 300);
+
+    // Anti-sniping still applies as it did before
+    env.ledger().set_timestamp(1900);
+    client.place_bid(&token_admin, &1, &150);
+    assert_eq!(client.get_auction(&1).unwrap().settings.end_time, 2200);
+
+    env.ledger().set_timestamp(2201);
+    client.settle_auction(&1);
+    assert_eq!(nft.owner_of(&7), token_admin);
+    assert_eq!(token::Client::new(&env, &token_contract_id).balance(&seller), 150);
+    assert!(client.get_auction(&1).unwrap().settled);
+}
+
+#[test]
+fn test_auction_with_achievement_nft() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let nft = AchievementNFTClient::new(&env, &env.register_contract(None, AchievementNFT));
+    let nft_admin = Address::generate(&env);
+    nft.initialize(&nft_admin);
+    let seller = Address::generate(&env);
+    let metadata = String::from_str(&env, "achievement");
+    let token_id = nft.craftmint(&nft_admin, &seller, &42, &metadata);
+
+    let token_admin = Address::generate(&env);
+    let token_contract_id = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+    let bidder = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &token_contract_id).mint(&bidder, &1000);
+    let client = AuctionContractClient::new(&env, &env.register_contract(None, AuctionContract));
+    client.init(&token_admin);
+
+    let auction_id = client.create_auction(
+        &seller,
+        &nft.address,
+        &(token_id as u64),
+        &token_contract_id,
+        &AuctionType::English,
+        &english_settings(None),
+    );
+    assert_eq!(nft.owner_of(&token_id), client.address);
+
+    env.ledger().set_timestamp(1500);
+    client.place_bid(&bidder, &auction_id, &150);
+    env.ledger().set_timestamp(2001);
+    client.settle_auction(&auction_id);
+
+    assert_eq!(nft.owner_of(&token_id), bidder);
+    assert_eq!(token::Client::new(&env, &token_contract_id).balance(&seller), 150);
+}
+
+#[test]
+#[should_panic(expected = "not owner")]
+fn test_create_requires_seller_to_own_nft() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token_admin = Address::generate(&env);
+    let token_contract_id = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+    let client = AuctionContractClient::new(&env, &env.register_contract(None, AuctionContract));
+    client.init(&token_admin);
+
+    let nft = mock_nft(&env, &Address::generate(&env), 7);
+    client.create_auction(
+        &Address::generate(&env),
+        &nft.address,
+        &7u64,
+        &token_contract_id,
+        &AuctionType::English,
+        &english_settings(None),
+    );
+}
+
+struct SealedSetup {
+    client: AuctionContractClient<'static>,
+    token: token::Client<'static>,
+    nft: MockNftClient<'static>,
+    seller: Address,
+    bidders: [Address; 3],
+    auction_id: u64,
+}
+
+// Commit 1000..=2000, reveal until 3000
+fn setup_sealed(env: &Env, reserve_price: i128) -> SealedSetup {
+    let token_admin = Address::generate(env);
+    let token_contract_id = env.register_stellar_asset_contract_v2(token_admin.clone()).address();
+    let token_admin_client = token::StellarAssetClient::new(env, &token_contract_id);
+
+    let client = AuctionContractClient::new(env, &env.register_contract(None, AuctionContract));
+    client.init(&token_admin);
+
+    let bidders = [
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    ];
+    for bidder in bidders.iter() {
+        token_admin_client.mint(bidder, &1000);
+    }
+
+    let settings = AuctionSettings {
+        start_time: 1000,
+        end_time: 2000,
+        starting_price: 50,
+        reserve_price,
+        buy_now_price: 0,
+        min_bid_increment: 0,
+        extension_window: 0,
+        extension_amount: 0,
+        max_extensions: None,
+        reveal_end_time: 3000,
+    };
+    let seller = Address::generate(env);
+    let nft = mock_nft(env, &seller, 9);
+    let auction_id = client.create_auction(
+        &seller,
+        &nft.address,
+        &9u64,
+        &token_contract_id,
+        &AuctionType::SealedBid,
+        &settings,
+    );
+    SealedSetup {
+        client,
+        token: token::Client::new(env, &token_contract_id),
+        nft,
+        seller,
+        bidders,
+        auction_id,
+    }
+}
+
+fn salt(env: &Env, seed: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[seed; 32])
+}
+
+fn commit(env: &Env, setup: &SealedSetup, bidder: &Address, amount: i128, deposit: i128) {
+    let commitment = setup.client.compute_commitment(
+        bidder,
+        &setup.auction_id,
+        &amount,
+        &salt(env, amount as u8),
+    );
+    setup
+        .client
+        .commit_bid(bidder, &setup.auction_id, &commitment, &deposit);
+}
+
+fn reveal(env: &Env, setup: &SealedSetup, bidder: &Address, amount: i128) {
+    setup
+        .client
+        .reveal_bid(bidder, &setup.auction_id, &amount, &salt(env, amount as u8));
+}
+
+#[test]
+fn test_sealed_bid_second_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sealed(&env, 100);
+    let [alice, bob, carol] = setup.bidders.clone();
+
+    assert_eq!(
+        setup.client.get_phase(&setup.auction_id),
+        AuctionPhase::Pending
+    );
+    env.ledger().set_timestamp(1500);
+    assert_eq!(
+        setup.client.get_phase(&setup.auction_id),
+        AuctionPhase::Commit
+    );
+
+    commit(&env, &setup, &alice, 300, 400);
+    commit(&env, &setup, &bob, 200, 200);
+    commit(&env, &setup, &carol, 250, 600);
+    assert_eq!(setup.token.balance(&setup.client.address), 1200);
+    assert_eq!(setup.client.get_bid_count(&setup.auction_id), 3);
+    let page = setup.client.get_bidders(&setup.auction_id, &1, &5);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap(), bob);
+    assert_eq!(page.get(1).unwrap(), carol);
+
+    env.ledger().set_timestamp(2500);
+    assert_eq!(
+        setup.client.get_phase(&setup.auction_id),
+        AuctionPhase::Reveal
+    );
+    reveal(&env, &setup, &bob, 200);
+    reveal(&env, &setup, &alice, 300);
+    // carol never reveals
+
+    let auction = setup.client.get_auction(&setup.auction_id).unwrap();
+    assert_eq!(auction.highest_bidder, Some(alice.clone()));
+    assert_eq!(auction.current_bid, 300);
+    assert_eq!(auction.second_bid, 200);
+    assert_eq!(setup.client.get_clearing_price(&setup.auction_id), 200);
+
+    env.ledger().set_timestamp(3001);
+    assert_eq!(
+        setup.client.get_phase(&setup.auction_id),
+        AuctionPhase::Ended
+    );
+    setup.client.settle_auction(&setup.auction_id);
+    assert_eq!(
+        setup.client.get_phase(&setup.auction_id),
+        AuctionPhase::Settled
+    );
+
+    assert_eq!(setup.nft.owner_of(&9), alice);
+    assert_eq!(setup.token.balance(&setup.seller), 200);
+
+    // Pull-based refunds: change for the winner, full deposits otherwise
+    assert_eq!(setup.client.withdraw(&alice, &setup.auction_id), 200);
+    assert_eq!(setup.client.withdraw(&bob, &setup.auction_id), 200);
+    assert_eq!(setup.client.withdraw(&carol, &setup.auction_id), 600);
+    assert_eq!(setup.token.balance(&alice), 800);
+    assert_eq!(setup.token.balance(&bob), 1000);
+    assert_eq!(setup.token.balance(&carol), 1000);
+    assert_eq!(setup.token.balance(&setup.client.address), 0);
+}
+
+#[test]
+fn test_sealed_single_bid_pays_reserve() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sealed(&env, 100);
+    let alice = setup.bidders[0].clone();
+
+    env.ledger().set_timestamp(1500);
+    commit(&env, &setup, &alice, 180, 180);
+    env.ledger().set_timestamp(2500);
+    reveal(&env, &setup, &alice, 180);
+    env.ledger().set_timestamp(3001);
+    setup.client.settle_auction(&setup.auction_id);
+
+    assert_eq!(setup.token.balance(&setup.seller), 100);
+    assert_eq!(setup.client.withdraw(&alice, &setup.auction_id), 80);
+}
+
+#[test]
+fn test_sealed_reserve_not_met_returns_nft() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sealed(&env, 500);
+    let alice = setup.bidders[0].clone();
+
+    env.ledger().set_timestamp(1500);
+    commit(&env, &setup, &alice, 300, 300);
+    env.ledger().set_timestamp(2500);
+    reveal(&env, &setup, &alice, 300);
+    env.ledger().set_timestamp(3001);
+    setup.client.settle_auction(&setup.auction_id);
+
+    assert_eq!(setup.nft.owner_of(&9), setup.seller);
+    assert_eq!(setup.client.withdraw(&alice, &setup.auction_id), 300);
+    assert_eq!(setup.token.balance(&setup.seller), 0);
+}
+
+#[test]
+#[should_panic(expected = "Commitment mismatch")]
+fn test_sealed_reveal_must_match_commitment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sealed(&env, 100);
+    let alice = setup.bidders[0].clone();
+
+    env.ledger().set_timestamp(1500);
+    commit(&env, &setup, &alice, 300, 400);
+    env.ledger().set_timestamp(2500);
+    setup
+        .client
+        .reveal_bid(&alice, &setup.auction_id, &350, &salt(&env, 44));
+}
+
+#[test]
+#[should_panic(expected = "Deposit does not cover bid")]
+fn test_sealed_deposit_must_cover_bid() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sealed(&env, 100);
+    let alice = setup.bidders[0].clone();
+
+    env.ledger().set_timestamp(1500);
+    commit(&env, &setup, &alice, 300, 100);
+    env.ledger().set_timestamp(2500);
+    reveal(&env, &setup, &alice, 300);
+}
+
+#[test]
+#[should_panic(expected = "Auction is not in the reveal phase")]
+fn test_sealed_reveal_during_commit_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sealed(&env, 100);
+    let alice = setup.bidders[0].clone();
+
+    env.ledger().set_timestamp(1500);
+    commit(&env, &setup, &alice, 300, 300);
+    reveal(&env, &setup, &alice, 300);
+}
+
+#[test]
+#[should_panic(expected = "Winner must wait for settlement")]
+fn test_sealed_winner_withdraw_waits_for_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sealed(&env, 100);
+    let alice = setup.bidders[0].clone();
+
+    env.ledger().set_timestamp(1500);
+    commit(&env, &setup, &alice, 300, 300);
+    env.ledger().set_timestamp(2500);
+    reveal(&env, &setup, &alice, 300);
+    env.ledger().set_timestamp(3001);
+    setup.client.withdraw(&alice, &setup.auction_id);
+}
+
+#[test]
+#[should_panic(expected = "Auction has bids")]
+fn test_sealed_cancel_after_commit_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sealed(&env, 100);
+    let [alice, _, _] = setup.bidders.clone();
+
+    env.ledger().set_timestamp(1500);
+    commit(&env, &setup, &alice, 300, 300);
+    setup
+        .client
+        .cancel_auction(&setup.seller, &setup.auction_id);
+}
+
+#[test]
+#[should_panic(expected = "Deposit already withdrawn")]
+fn test_sealed_withdraw_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let setup = setup_sealed(&env, 100);
+    let [alice, bob, _carol] = setup.bidders.clone();
+
+    env.ledger().set_timestamp(1500);
+    commit(&env, &setup, &alice, 300, 300);
+    commit(&env, &setup, &bob, 200, 200);
+    env.ledger().set_timestamp(2500);
+    reveal(&env, &setup, &alice, 300);
+    reveal(&env, &setup, &bob, 200);
+    env.ledger().set_timestamp(3001);
+    setup.client.withdraw(&bob, &setup.auction_id);
+    setup.client.withdraw(&bob, &setup.auction_id);
+}
@@ -219,6 +219,7 @@ impl CraftingContract {
                 &recipe.output_token_address,
                 &symbol_short!("craftmint"),
                 Vec::from_array(&env, [
+                    env.current_contract_address().into_val(&env),
                     player.into_val(&env),
                     recipe.output_token_id.into(),
                     String::from_str(&env, "Crafted achievement").into_val(&env),
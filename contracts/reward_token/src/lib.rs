@@ -1,6 +1,6 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Vec};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Symbol, Vec};
 
 #[contracttype]
 pub enum DataKey {
@@ -239,7 +239,7 @@ impl RewardToken {
         env: Env,
         spender: Address,
         amount: i128,
-        _unlock_type: String,
+        unlock_type: String,
     ) {
         spender.require_auth();
 
@@ -255,7 +255,7 @@ impl RewardToken {
         // Deduct from balance (burn)
         env.storage()
             .instance()
-            .set(&DataKey::Balance(spender), &(balance - amount));
+            .set(&DataKey::Balance(spender.clone()), &(balance - amount));
 
         // Reduce total supply
         let total_supply: i128 = env
@@ -266,6 +266,9 @@ impl RewardToken {
         env.storage()
             .instance()
             .set(&DataKey::TotalSupply, &(total_supply - amount));
+
+        env.events()
+            .publish((Symbol::new(&env, "unlock"), spender), (amount, unlock_type));
     }
 
     /// Burn tokens (reduce total supply)